@@ -1,30 +1,116 @@
 #![allow(non_snake_case, non_upper_case_globals)]
 #![cfg_attr(debug_assertions, allow(dead_code))]
 
+use chrono::Local;
 use dioxus::prelude::*;
-use fermi::use_atom_ref;
-use crate::components::{DownloadElement, Options};
+use fermi::{use_atom_ref, use_read, use_set};
+use std::time::Duration;
+use crate::components::{useSubscriptionChecks, DownloadElement, Options, PlaylistGroupElement, SubscriptionsView};
 use crate::hooks::useOnce;
-use crate::state::{loadOptions, UrlList};
+use crate::download::{freeSpace, ByteSize};
+use crate::state::{enqueueDownload, loadOptions, loadSubscriptions, DownloadEntry, DownloaderOptions, ExpandPlaylists,
+	LowDiskSpace, ScheduleOpen, ShowOptions, ShowSubscriptions, Subscriptions, UrlList};
+
+/// How often the free space on the output disk is checked against the minimum.
+const DiskSpaceCheckInterval: Duration = Duration::from_secs(30);
+/// The longest the schedule goes without being checked, so that changes to it are picked up.
+const ScheduleCheckInterval: Duration = Duration::from_secs(60);
 
 pub fn App(cx: Scope) -> Element
 {
 	fermi::use_init_atom_root(cx);
 	
+	let downloaderOptions = use_atom_ref(cx, DownloaderOptions);
+	let expandPlaylists = use_read(cx, ExpandPlaylists);
+	let lowDiskSpace = use_read(cx, LowDiskSpace);
+	let setLowDiskSpace = use_set(cx, LowDiskSpace);
+	let setScheduleOpen = use_set(cx, ScheduleOpen);
 	let urlList = use_atom_ref(cx, UrlList);
 	
 	let videoUrl = use_state(cx, || String::default());
-	let showOptions = use_state(cx, || false);
+	let showOptions = use_read(cx, ShowOptions);
+	let setShowOptions = use_set(cx, ShowOptions);
+	let showSubscriptions = use_read(cx, ShowSubscriptions);
+	let setShowSubscriptions = use_set(cx, ShowSubscriptions);
+	let subscriptions = use_atom_ref(cx, Subscriptions);
 	
 	useOnce(cx, || loadOptions(cx));
+	useOnce(cx, || *subscriptions.write() = loadSubscriptions());
+	useSubscriptionChecks(cx);
+	
+	//Hold and pause the downloads while the output disk is nearly full
+	use_future(cx, (), |_|
+	{
+		to_owned![downloaderOptions, setLowDiskSpace];
+		async move
+		{
+			let mut low = None;
+			loop
+			{
+				let (path, minimum) = {
+					let opts = downloaderOptions.read();
+					(opts.outputPath.to_owned(), ByteSize::parse(opts.diskSpaceMinimum.to_owned()).ok())
+				};
+				
+				let free = match minimum
+				{
+					Some(m) => freeSpace(path).ok().filter(|free| *free < m),
+					None => None,
+				};
+				
+				if free != low
+				{
+					setLowDiskSpace(free);
+					low = free;
+				}
+				
+				tokio::time::sleep(DiskSpaceCheckInterval).await;
+			}
+		}
+	});
+	
+	//Open and close the schedule at its boundaries, the downloads start or change their rates in response
+	use_future(cx, (), |_|
+	{
+		to_owned![downloaderOptions, setScheduleOpen];
+		async move
+		{
+			let mut open = true;
+			loop
+			{
+				let now = Local::now().naive_local();
+				let schedule = downloaderOptions.read().schedule.to_owned();
+				if schedule.isOpen(now) != open
+				{
+					open = !open;
+					setScheduleOpen(open);
+				}
+				
+				let wait = schedule.nextBoundary(now)
+					.and_then(|boundary| (boundary - now).to_std().ok())
+					.map_or(ScheduleCheckInterval, |d| d.min(ScheduleCheckInterval));
+				tokio::time::sleep(wait).await;
+			}
+		}
+	});
+	
+	let entries = urlList.read()
+		.iter()
+		.map(|(key, entry)| (*key, entry.to_owned()))
+		.collect::<Vec<(usize, DownloadEntry)>>();
 	
 	return cx.render(rsx!
 	{
-		if **showOptions
+		if *showOptions
 		{
 			rsx!(Options {})
 		}
 		
+		if *showSubscriptions
+		{
+			rsx!(SubscriptionsView {})
+		}
+		
 		div
 		{
 			class: "app",
@@ -43,7 +129,7 @@ pub fn App(cx: Scope) -> Element
 				button
 				{
 					id: "showOptions",
-					onclick: move |_| showOptions.set(!showOptions),
+					onclick: move |_| setShowOptions(!*showOptions),
 					
 					img { alt: "Options", src: "./assets/options.png" }
 				}
@@ -59,31 +145,70 @@ pub fn App(cx: Scope) -> Element
 					{
 						if !videoUrl.is_empty()
 						{
-							let len = urlList.read().len();
-							let mut urls = urlList.write();
-							urls.values()
-								.find(|v| **v == videoUrl.to_string())
-								.is_none()
-								.then(|| {
-									urls.insert(len, videoUrl.to_string());
-									videoUrl.set(String::default());
-								});
+							let entry = DownloadEntry
+							{
+								url: videoUrl.to_string(),
+								expandPlaylist: *expandPlaylists,
+								profile: None,
+							};
+							
+							if enqueueDownload(&mut urlList.write(), entry)
+							{
+								videoUrl.set(String::default());
+							}
 						}
 					},
 					
 					"Download"
 				}
+				
+				button
+				{
+					onclick: move |_| setShowSubscriptions(!*showSubscriptions),
+					"Subscriptions"
+				}
 			}
 			
+			lowDiskSpace.map(|free| rsx!
+			{
+				h6 { class: "lowDiskSpace", "Downloads are paused, only {free} is free on the output disk" }
+			})
+			
 			hr {}
 			
 			div
 			{
 				id: "downloads",
 				
-				for (key, url) in &*urlList.read()
+				for (key, entry) in entries.into_iter()
 				{
-					DownloadElement { key: "{key}", indexKey: *key, videoUrl: url.to_owned() }
+					if entry.expandPlaylist
+					{
+						rsx!
+						{
+							PlaylistGroupElement
+							{
+								key: "{key}",
+								indexKey: key,
+								playlistUrl: entry.url,
+								onRemove: move |k: usize| { urlList.write().remove(&k); }
+							}
+						}
+					}
+					else
+					{
+						rsx!
+						{
+							DownloadElement
+							{
+								key: "{key}",
+								indexKey: key,
+								videoUrl: entry.url,
+								profile: entry.profile,
+								onRemove: move |k: usize| { urlList.write().remove(&k); }
+							}
+						}
+					}
 				}
 			}
 		}
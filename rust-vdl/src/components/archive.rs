@@ -0,0 +1,85 @@
+#![allow(non_snake_case, non_upper_case_globals)]
+#![cfg_attr(debug_assertions, allow(dead_code))]
+
+use dioxus::prelude::*;
+use crate::download::{readArchive, writeArchive, ArchiveEntry};
+
+/// Lists the videos recorded in a download archive and allows removing them
+/// so that they will be downloaded again. Removals are saved immediately.
+#[inline_props]
+pub fn ArchiveEditor(cx: Scope, path: String) -> Element
+{
+	let entries = use_ref(cx, || readArchive(path.to_owned()));
+	let search = use_state(cx, || String::default());
+	
+	let needle = search.get().to_lowercase();
+	let visible = entries.read()
+		.iter()
+		.enumerate()
+		.filter(|(_, entry)| needle.is_empty() || entry.to_string().to_lowercase().contains(needle.as_str()))
+		.map(|(i, entry)| (i, entry.extractor.to_owned(), entry.id.to_owned()))
+		.collect::<Vec<(usize, String, String)>>();
+	
+	let total = entries.read().len();
+	let shown = visible.len();
+	
+	return cx.render(rsx!
+	{
+		div
+		{
+			class: "archiveEditor",
+			
+			div
+			{
+				class: "row",
+				
+				h5 { "{shown} of {total} entries" }
+				
+				input
+				{
+					r#type: "text",
+					placeholder: "Search",
+					value: "{search}",
+					oninput: move |evt: FormEvent| search.set(evt.value.to_owned())
+				}
+				
+				button
+				{
+					onclick: move |_| *entries.write() = readArchive(path.to_owned()),
+					"Reload"
+				}
+			}
+			
+			ul
+			{
+				for (i, extractor, id) in visible.into_iter()
+				{
+					li
+					{
+						key: "{extractor}{id}",
+						
+						span { class: "extractor", "{extractor}" }
+						span { class: "id", "{id}" }
+						
+						button
+						{
+							onclick: move |_| {
+								let mut list = entries.write();
+								if i < list.len()
+								{
+									let removed: ArchiveEntry = list.remove(i);
+									if !writeArchive(path.to_owned(), &list)
+									{
+										//Keep the view in sync with the file when saving fails
+										list.insert(i, removed);
+									}
+								}
+							},
+							"Remove"
+						}
+					}
+				}
+			}
+		}
+	});
+}
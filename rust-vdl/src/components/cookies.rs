@@ -0,0 +1,119 @@
+#![allow(non_snake_case, non_upper_case_globals)]
+#![cfg_attr(debug_assertions, allow(dead_code))]
+
+use dioxus::prelude::*;
+use crate::download::{detectBrowserProfiles, Browser, BrowserKeyring, CookiesFromBrowser};
+
+/// Picks the browser, and optionally the profile, keyring and container, to
+/// load cookies from. Profiles found on this machine are offered in a list,
+/// otherwise a profile name or path can be typed in.
+#[inline_props]
+pub fn BrowserCookiesEditor<'a>(cx: Scope, value: CookiesFromBrowser, onChange: EventHandler<'a, CookiesFromBrowser>) -> Element<'a>
+{
+	let detected = use_state(cx, || detectBrowserProfiles());
+	
+	let browsers = Browser::All.iter()
+		.map(|b| (b.to_string(), Some(*b) == value.browser))
+		.collect::<Vec<(String, bool)>>();
+	let noBrowser = value.browser.is_none();
+	
+	let profile = value.profile.to_owned();
+	let profiles = value.browser.map_or(vec![], |browser| detected.iter()
+		.filter(|p| p.browser == browser)
+		.map(|p| {
+			let path = p.path.to_string_lossy().to_string();
+			let selected = path == profile;
+			(p.name.to_owned(), path, selected)
+		})
+		.collect::<Vec<(String, String, bool)>>());
+	let defaultProfile = profile.is_empty();
+	
+	//Only Chromium based browsers on Linux keep their key in a keyring
+	let showKeyring = cfg!(target_os = "linux") && value.browser.map_or(false, |b| b != Browser::Firefox && b != Browser::Safari);
+	let keyrings = BrowserKeyring::All.iter()
+		.map(|k| (k.key().to_string(), Some(*k) == value.keyring))
+		.collect::<Vec<(String, bool)>>();
+	let noKeyring = value.keyring.is_none();
+	
+	let showContainer = value.browser.map_or(false, |b| b.hasContainers());
+	let container = value.container.to_owned();
+	
+	return cx.render(rsx!
+	{
+		div
+		{
+			class: "browserCookiesEditor",
+			
+			select
+			{
+				onchange: move |evt: FormEvent| {
+					let browser = evt.value.parse::<usize>().ok().and_then(|b| Browser::All.get(b).copied());
+					//Profiles and keyrings do not carry over between browsers
+					onChange.call(CookiesFromBrowser { browser, ..CookiesFromBrowser::default() });
+				},
+				
+				option { value: "", selected: "{noBrowser}", "None" }
+				for (b, (name, selected)) in browsers.into_iter().enumerate()
+				{
+					option { key: "{b}", value: "{b}", selected: "{selected}", "{name}" }
+				}
+			}
+			
+			(!noBrowser && !profiles.is_empty()).then(|| rsx!
+			{
+				select
+				{
+					title: "Browser profile",
+					onchange: move |evt: FormEvent| onChange.call(CookiesFromBrowser { profile: evt.value.to_owned(), ..value.to_owned() }),
+					
+					option { value: "", selected: "{defaultProfile}", "Default Profile" }
+					for (name, path, selected) in profiles.iter()
+					{
+						option { key: "{path}", value: "{path}", selected: "{selected}", "{name}" }
+					}
+				}
+			})
+			
+			(!noBrowser && profiles.is_empty()).then(|| rsx!
+			{
+				input
+				{
+					r#type: "text",
+					placeholder: "Profile name or path",
+					value: "{profile}",
+					oninput: move |evt: FormEvent| onChange.call(CookiesFromBrowser { profile: evt.value.trim().to_owned(), ..value.to_owned() })
+				}
+			})
+			
+			showKeyring.then(|| rsx!
+			{
+				select
+				{
+					title: "Keyring the cookie key is stored in",
+					onchange: move |evt: FormEvent| {
+						let keyring = evt.value.parse::<usize>().ok().and_then(|k| BrowserKeyring::All.get(k).copied());
+						onChange.call(CookiesFromBrowser { keyring, ..value.to_owned() });
+					},
+					
+					option { value: "", selected: "{noKeyring}", "Automatic Keyring" }
+					for (k, (name, selected)) in keyrings.into_iter().enumerate()
+					{
+						option { key: "{k}", value: "{k}", selected: "{selected}", "{name}" }
+					}
+				}
+			})
+			
+			showContainer.then(|| rsx!
+			{
+				input
+				{
+					r#type: "text",
+					placeholder: "Container",
+					title: "Firefox container to load cookies from, or none",
+					value: "{container}",
+					oninput: move |evt: FormEvent| onChange.call(CookiesFromBrowser { container: evt.value.trim().to_owned(), ..value.to_owned() })
+				}
+			})
+		}
+	});
+}
@@ -0,0 +1,840 @@
+#![allow(non_snake_case, non_upper_case_globals)]
+#![cfg_attr(debug_assertions, allow(dead_code))]
+
+use chrono::Local;
+use dioxus::prelude::*;
+use fermi::{use_atom_ref, use_atom_state, AtomState, UseAtomRef};
+use futures::StreamExt;
+use futures::channel::mpsc::{unbounded, UnboundedSender};
+use log::{debug, error, warn};
+use std::collections::{BTreeMap, BTreeSet};
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use crate::download::{checkDiskSpace, formatOpening, killProcessGroup, limitRateFor, pauseProcessGroup, resumeProcessGroup,
+	validateCookieFile, AutoRetry, ByteSize, DownloadChapter, DownloadError, DownloadHandlers, DownloadProgress,
+	DownloadPrompt, DownloadReset, DownloadSection, DownloadSkipped, DownloadStarted, DownloadStatus, DownloadStopped,
+	DownloadTitle, DownloadWaiting, SubscriptionProfile, VideoDownloader, VideoDownloaderOptions, VideoMetadata};
+use crate::secrets::Secrets;
+use crate::state::{nextDownloadId, recordHistory, ActiveDownloads, Binary, DownloaderOptions, DownloadSlots,
+	HistoryRecord, ScheduleOpen, UnlockedSecrets};
+
+const Reason_LowDiskSpace: &str = "the output disk is nearly full";
+/// Seconds the active downloads must stay the same before rate limits are reapplied.
+const RateSettleDelay: u64 = 5;
+
+/// The state of one download entry. The handles are cloned once when the
+/// entry is created, so values are read with `current()` rather than `get()`.
+#[derive(Clone)]
+pub struct DownloadState
+{
+	pub appliedRate: UseRef<String>,
+	pub chapterFiles: UseRef<Vec<DownloadChapter>>,
+	pub childPid: UseRef<Option<u32>>,
+	pub continuePartial: UseRef<bool>,
+	pub credentials: UseRef<(String, String)>,
+	pub failure: UseState<Option<DownloadError>>,
+	pub itemsDownloaded: UseRef<bool>,
+	pub metadata: UseState<Option<VideoMetadata>>,
+	pub pausedForSpace: UseRef<bool>,
+	pub pauseGeneration: UseRef<usize>,
+	pub playlistCurrent: UseState<usize>,
+	pub playlistItems: UseRef<String>,
+	pub playlistMax: UseState<usize>,
+	pub process: UseState<Option<JoinHandle<()>>>,
+	pub progressBars: UseRef<Vec<(String, DownloadProgress)>>,
+	pub prompt: UseState<Option<DownloadPrompt>>,
+	pub promptAnswers: UseRef<Option<UnboundedSender<String>>>,
+	pub rateOverride: UseState<String>,
+	pub retryAttempt: UseRef<usize>,
+	pub retryDue: UseState<bool>,
+	pub retryGeneration: UseRef<usize>,
+	pub retryPolicy: UseRef<AutoRetry>,
+	pub savedLocation: UseState<Option<String>>,
+	pub sections: UseRef<Vec<DownloadSection>>,
+	pub shouldReset: UseRef<bool>,
+	pub showCredentials: UseState<bool>,
+	pub spaceWarning: UseState<Option<String>>,
+	pub status: UseState<DownloadStatus>,
+	pub title: UseState<String>,
+}
+
+/// The coroutines which receive the output of a running yt-dlp process.
+#[derive(Clone)]
+struct DownloadEvents
+{
+	chapter: Coroutine<DownloadChapter>,
+	progress: Coroutine<DownloadProgress>,
+	prompt: Coroutine<DownloadPrompt>,
+	reset: Coroutine<DownloadReset>,
+	skipped: Coroutine<DownloadSkipped>,
+	started: Coroutine<DownloadStarted>,
+	stopped: Coroutine<DownloadStopped>,
+	title: Coroutine<DownloadTitle>,
+	waiting: Coroutine<DownloadWaiting>,
+}
+
+impl DownloadEvents
+{
+	fn handlers(&self) -> DownloadHandlers
+	{
+		let DownloadEvents { chapter, progress, prompt, reset, skipped, started, stopped, title, waiting } = self.clone();
+		return DownloadHandlers
+		{
+			chapter: Box::new(move |dc| chapter.send(dc)),
+			progress: Box::new(move |dp| progress.send(dp)),
+			prompt: Box::new(move |dp| prompt.send(dp)),
+			reset: Box::new(move |dr| reset.send(dr)),
+			skipped: Box::new(move |ds| skipped.send(ds)),
+			started: Box::new(move |ds| started.send(ds)),
+			stopped: Box::new(move |ds| stopped.send(ds)),
+			title: Box::new(move |dt| title.send(dt)),
+			waiting: Box::new(move |dw| waiting.send(dw)),
+		};
+	}
+}
+
+// --------------------------------------------------
+
+/// Starts, stops and restarts the yt-dlp process of one download entry. Kept
+/// apart from rendering so that the effects below can drive it as well as the
+/// buttons.
+#[derive(Clone)]
+pub struct DownloadControl
+{
+	/// Unique for as long as the app runs, unlike the key of the entry in its list.
+	pub id: usize,
+	pub state: DownloadState,
+	activeDownloads: UseAtomRef<BTreeMap<usize, Option<ByteSize>>>,
+	binary: AtomState<String>,
+	downloaderOptions: UseAtomRef<VideoDownloaderOptions>,
+	downloadSlots: UseAtomRef<BTreeSet<usize>>,
+	events: DownloadEvents,
+	profile: Option<SubscriptionProfile>,
+	scheduleOpen: AtomState<bool>,
+	secrets: UseAtomRef<Secrets>,
+	url: String,
+}
+
+impl DownloadControl
+{
+	/// Whether yt-dlp is running and not suspended.
+	pub fn isActive(&self) -> bool
+	{
+		return matches!(*self.state.status.current(), DownloadStatus::Downloading | DownloadStatus::Waiting(_))
+			&& self.state.process.current().is_some();
+	}
+	
+	/// Whether the entry has yet to start, for whatever reason.
+	pub fn isWaiting(&self) -> bool
+	{
+		return matches!(*self.state.status.current(), DownloadStatus::Held(_) | DownloadStatus::Queued | DownloadStatus::Scheduled(_));
+	}
+	
+	/// Whether the entry counts against the Max Concurrent Downloads.
+	pub fn holdsSlot(&self) -> bool
+	{
+		return matches!(*self.state.status.current(), DownloadStatus::Downloading | DownloadStatus::Paused
+			| DownloadStatus::Retrying { .. } | DownloadStatus::Waiting(_));
+	}
+	
+	pub fn hasSlot(&self) -> bool
+	{
+		return self.downloadSlots.read().contains(&self.id);
+	}
+	
+	pub fn hasFreeSlot(&self) -> bool
+	{
+		let max = self.downloaderOptions.read().maxConcurrentDownloads;
+		return max == 0 || self.hasSlot() || self.downloadSlots.read().len() < max;
+	}
+	
+	pub fn ownRate(&self) -> Option<ByteSize>
+	{
+		return ByteSize::parse(self.state.rateOverride.current().to_string()).ok();
+	}
+	
+	/// The rate to start yt-dlp with: this entry's own, its share of the global budget, or the per-process limit.
+	pub fn targetLimitRate(&self) -> String
+	{
+		let options = self.downloaderOptions.read();
+		let budget = options.schedule.budget(*self.scheduleOpen.current(), options.bandwidthLimit.to_owned());
+		return limitRateFor(self.id, &self.activeDownloads.read(), budget, self.ownRate())
+			.map_or(options.limitRate.to_owned(), |rate| rate.bytes().to_string());
+	}
+	
+	fn scheduledStatus(&self) -> DownloadStatus
+	{
+		let now = Local::now().naive_local();
+		let opening = self.downloaderOptions.read().schedule.nextOpening(now)
+			.map_or("the next window".to_string(), |o| formatOpening(o, now));
+		return DownloadStatus::Scheduled(opening);
+	}
+	
+	fn setStatus(&self, status: DownloadStatus)
+	{
+		if *self.state.status.current() != status
+		{
+			self.state.status.set(status);
+		}
+	}
+	
+	/// Start from the beginning, counting the retries afresh.
+	pub fn start(&self)
+	{
+		*self.state.retryAttempt.write() = 0;
+		self.state.status.set(DownloadStatus::Downloading);
+		self.run();
+	}
+	
+	/// Stop and start again, e.g. to fetch other sections or playlist items.
+	pub fn restart(&self)
+	{
+		*self.state.shouldReset.write() = true;
+		self.abort();
+		self.start();
+	}
+	
+	pub fn halt(&self)
+	{
+		self.abort();
+		self.state.process.set(None);
+		self.state.status.set(DownloadStatus::Halted);
+	}
+	
+	/// Stop the download and give up its share of the bandwidth and its slot.
+	pub fn remove(&self)
+	{
+		self.abort();
+		self.state.process.set(None);
+		self.activeDownloads.write().remove(&self.id);
+		self.downloadSlots.write().remove(&self.id);
+	}
+	
+	/// Start with the password and two-factor code entered for this attempt.
+	pub fn submitCredentials(&self, password: String, twoFactor: String)
+	{
+		*self.state.credentials.write() = (password, twoFactor);
+		self.state.showCredentials.set(false);
+		self.state.status.set(DownloadStatus::Downloading);
+		self.run();
+	}
+	
+	pub fn pause(&self)
+	{
+		let paused = self.state.childPid.read().map(pauseProcessGroup);
+		*self.state.pauseGeneration.write() += 1;
+		self.state.status.set(DownloadStatus::Paused);
+		
+		//Without a process group to suspend, stop now and continue from the partial files on resume
+		if !matches!(paused, Some(Ok(_)))
+		{
+			if let Some(Err(e)) = paused
+			{
+				warn!("{}", e);
+			}
+			self.stopPaused();
+		}
+	}
+	
+	pub fn resume(&self)
+	{
+		let resumed = self.state.childPid.read().map(resumeProcessGroup);
+		match resumed
+		{
+			Some(Ok(_)) => self.state.status.set(DownloadStatus::Downloading),
+			//The process group was stopped while paused, start again from the partial files
+			_ => {
+				if let Some(Err(e)) = resumed
+				{
+					warn!("{}", e);
+				}
+				*self.state.continuePartial.write() = true;
+				self.state.status.set(DownloadStatus::Downloading);
+				self.run();
+			},
+		}
+	}
+	
+	/// Stop a paused download, to be continued from its partial files on resume.
+	fn stopPaused(&self)
+	{
+		self.abort();
+		self.state.process.set(None);
+		*self.state.continuePartial.write() = true;
+	}
+	
+	//Aborting the task only kills yt-dlp itself, its ffmpeg children are in the same process group and have to be killed with it
+	pub fn abort(&self)
+	{
+		if let Some(handle) = self.state.process.current().as_ref()
+		{
+			handle.abort();
+		}
+		
+		let pid = self.state.childPid.write().take();
+		if let (true, Some(pid)) = (cfg!(unix), pid)
+		{
+			if let Err(e) = killProcessGroup(pid)
+			{
+				error!("{}", e);
+			}
+		}
+	}
+	
+	fn run(&self)
+	{
+		self.state.process.set(self.spawn());
+	}
+	
+	fn spawn(&self) -> Option<JoinHandle<()>>
+	{
+		let state = &self.state;
+		
+		//Started by hand or not, a running download always counts against the limit
+		self.downloadSlots.write().insert(self.id);
+		
+		let mut dlopts = self.downloaderOptions.read().clone();
+		if let Some(p) = &self.profile
+		{
+			p.apply(&mut dlopts);
+		}
+		dlopts.downloadSections = state.sections.read().clone();
+		//Partial files are kept when retrying or when a paused download had to be stopped
+		dlopts.continueDownload = *state.retryAttempt.read() > 0 || *state.continuePartial.read();
+		*state.continuePartial.write() = false;
+		*state.retryGeneration.write() += 1;
+		if !dlopts.continueDownload
+		{
+			*state.itemsDownloaded.write() = false;
+		}
+		*state.retryPolicy.write() = dlopts.autoRetry.to_owned();
+		dlopts.limitRate = self.targetLimitRate();
+		*state.appliedRate.write() = dlopts.limitRate.to_owned();
+		if !state.playlistItems.read().is_empty()
+		{
+			dlopts.playlistItems = state.playlistItems.read().clone();
+		}
+		
+		dlopts.password = self.secrets.read().password.to_owned();
+		dlopts.videoPassword = self.secrets.read().videoPassword.to_owned();
+		{
+			//Prompted credentials are only good for a single attempt
+			let mut prompted = state.credentials.write();
+			if !prompted.0.is_empty()
+			{
+				dlopts.password = prompted.0.to_owned();
+				dlopts.twoFactor = prompted.1.to_owned();
+			}
+			*prompted = (String::default(), String::default());
+		}
+		
+		//Without a password yt-dlp would block on a prompt nobody can answer
+		if dlopts.needsPassword()
+		{
+			state.showCredentials.set(true);
+			state.status.set(DownloadStatus::Halted);
+			return None;
+		}
+		
+		//A broken cookie file would otherwise only be warned about and the download run signed out
+		if !dlopts.cookies.is_empty()
+		{
+			if let Err(e) = validateCookieFile(dlopts.cookies.to_owned())
+			{
+				state.failure.set(Some(DownloadError::Internal(e)));
+				state.status.set(DownloadStatus::Failed);
+				return None;
+			}
+		}
+		
+		//Catch a full disk now rather than as a cryptic error part way through
+		let expected = state.metadata.current().as_ref().as_ref()
+			.and_then(|m| m.filesize.or(m.filesizeApprox))
+			.map(ByteSize);
+		//With a temporary path the file is downloaded there first, so both disks need the space
+		let mut spacePaths = vec![dlopts.outputPath.to_owned()];
+		if dlopts.paths.usesTemp()
+		{
+			spacePaths.push(dlopts.paths.resolvedTemp(dlopts.outputPath.to_owned()));
+		}
+		let spaceCheck = spacePaths.into_iter()
+			.map(|path| checkDiskSpace(path, expected, ByteSize::parse(dlopts.diskSpaceMargin.to_owned()).ok()))
+			.find(|check| check.is_err())
+			.unwrap_or(Ok(()));
+		match spaceCheck
+		{
+			Err(e) if !dlopts.diskSpaceWarnOnly => {
+				state.failure.set(Some(DownloadError::Internal(e)));
+				state.status.set(DownloadStatus::Failed);
+				return None;
+			},
+			Err(e) => {
+				warn!("{}", e);
+				state.spaceWarning.set(Some(e));
+			},
+			Ok(_) => state.spaceWarning.set(None),
+		}
+		
+		state.chapterFiles.write().clear();
+		state.failure.set(None);
+		state.savedLocation.set(None);
+		
+		let (answerSender, answerReceiver) = unbounded::<String>();
+		*state.promptAnswers.write() = Some(answerSender);
+		
+		let binary = self.binary.current().to_string();
+		let handlers = self.events.handlers();
+		let url = self.url.to_owned();
+		return Some(tokio::task::spawn(async move {
+			let mut vdl = VideoDownloader::new(binary, dlopts);
+			vdl.download(url, handlers, answerReceiver).await;
+		}));
+	}
+}
+
+// --------------------------------------------------
+
+/// Create the state and event coroutines of a download entry, and the control
+/// which drives them.
+pub fn useDownloadControl<'a>(cx: &'a ScopeState, url: &String, profile: &Option<SubscriptionProfile>) -> &'a DownloadControl
+{
+	let activeDownloads = use_atom_ref(cx, ActiveDownloads);
+	let binary = use_atom_state(cx, Binary);
+	let downloaderOptions = use_atom_ref(cx, DownloaderOptions);
+	let downloadSlots = use_atom_ref(cx, DownloadSlots);
+	let scheduleOpen = use_atom_state(cx, ScheduleOpen);
+	let secrets = use_atom_ref(cx, UnlockedSecrets);
+	
+	let state = DownloadState
+	{
+		appliedRate: use_ref(cx, || String::default()).clone(),
+		chapterFiles: use_ref(cx, || Vec::<DownloadChapter>::default()).clone(),
+		childPid: use_ref(cx, || None::<u32>).clone(),
+		continuePartial: use_ref(cx, || false).clone(),
+		credentials: use_ref(cx, || (String::default(), String::default())).clone(),
+		failure: use_state(cx, || None::<DownloadError>).clone(),
+		itemsDownloaded: use_ref(cx, || false).clone(),
+		metadata: use_state(cx, || None::<VideoMetadata>).clone(),
+		pausedForSpace: use_ref(cx, || false).clone(),
+		pauseGeneration: use_ref(cx, || 0 as usize).clone(),
+		playlistCurrent: use_state(cx, || 0 as usize).clone(),
+		playlistItems: use_ref(cx, || String::default()).clone(),
+		playlistMax: use_state(cx, || 0 as usize).clone(),
+		process: use_state(cx, || None::<JoinHandle<()>>).clone(),
+		progressBars: use_ref(cx, || Vec::<(String, DownloadProgress)>::default()).clone(),
+		prompt: use_state(cx, || None::<DownloadPrompt>).clone(),
+		promptAnswers: use_ref(cx, || None::<UnboundedSender<String>>).clone(),
+		rateOverride: use_state(cx, || String::default()).clone(),
+		retryAttempt: use_ref(cx, || 0 as usize).clone(),
+		retryDue: use_state(cx, || false).clone(),
+		retryGeneration: use_ref(cx, || 0 as usize).clone(),
+		retryPolicy: use_ref(cx, || AutoRetry::default()).clone(),
+		savedLocation: use_state(cx, || None::<String>).clone(),
+		sections: use_ref(cx, || Vec::<DownloadSection>::default()).clone(),
+		shouldReset: use_ref(cx, || false).clone(),
+		showCredentials: use_state(cx, || false).clone(),
+		spaceWarning: use_state(cx, || None::<String>).clone(),
+		//Queued until the effects below have decided whether it can start
+		status: use_state(cx, || DownloadStatus::Queued).clone(),
+		title: use_state(cx, || url.to_owned()).clone(),
+	};
+	
+	let events = useDownloadEvents(cx, &state, url);
+	
+	return cx.use_hook(|| DownloadControl
+	{
+		id: nextDownloadId(),
+		state,
+		activeDownloads: activeDownloads.clone(),
+		binary: binary.clone(),
+		downloaderOptions: downloaderOptions.clone(),
+		downloadSlots: downloadSlots.clone(),
+		events,
+		profile: profile.to_owned(),
+		scheduleOpen: scheduleOpen.clone(),
+		secrets: secrets.clone(),
+		url: url.to_owned(),
+	});
+}
+
+fn useDownloadEvents(cx: &ScopeState, state: &DownloadState, url: &String) -> DownloadEvents
+{
+	let vt = state.title.clone();
+	let title = use_coroutine(cx, |mut recv: UnboundedReceiver<DownloadTitle>| async move
+	{
+		while let Some(instance) = recv.next().await
+		{
+			if !instance.title.is_empty() && *vt.current() != instance.title
+			{
+				vt.set(instance.title.to_owned());
+			}
+		}
+	});
+	
+	let dpr = state.progressBars.clone();
+	let sr1 = state.shouldReset.clone();
+	let pid = state.itemsDownloaded.clone();
+	let pst = state.status.clone();
+	let progress = use_coroutine(cx, |mut recv: UnboundedReceiver<DownloadProgress>| async move
+	{
+		while let Some(instance) = recv.next().await
+		{
+			let mut list = dpr.write();
+			
+			let mut resetFlag = sr1.write();
+			if *resetFlag
+			{
+				list.clear();
+				*resetFlag = false;
+			}
+			
+			if let Some((_, prog)) = list.iter_mut().find(|(label, _)| label == &instance.label)
+			{
+				if prog.percentComplete != "100%"
+				{
+					prog.update(instance.to_owned());
+				}
+			}
+			else
+			{
+				list.push((instance.label.to_owned(), instance.to_owned()));
+			}
+			
+			//A later playlist item is downloading after an earlier one was skipped
+			*pid.write() = true;
+			if let DownloadStatus::Skipped(_) = *pst.current()
+			{
+				pst.set(DownloadStatus::Downloading);
+			}
+		}
+	});
+	
+	let pc = state.playlistCurrent.clone();
+	let pm = state.playlistMax.clone();
+	let sr2 = state.shouldReset.clone();
+	let reset = use_coroutine(cx, |mut recv: UnboundedReceiver<DownloadReset>| async move
+	{
+		while let Some(instance) = recv.next().await
+		{
+			*sr2.write() = true;
+			pc.set(instance.playlistCurrent);
+			pm.set(instance.playlistMax);
+		}
+	});
+	
+	let cf = state.chapterFiles.clone();
+	let chapter = use_coroutine(cx, |mut recv: UnboundedReceiver<DownloadChapter>| async move
+	{
+		while let Some(instance) = recv.next().await
+		{
+			cf.write().push(instance);
+		}
+	});
+	
+	let sst = state.status.clone();
+	let skipped = use_coroutine(cx, |mut recv: UnboundedReceiver<DownloadSkipped>| async move
+	{
+		while let Some(instance) = recv.next().await
+		{
+			sst.set(DownloadStatus::Skipped(instance.reason.to_string()));
+		}
+	});
+	
+	let wst = state.status.clone();
+	let waiting = use_coroutine(cx, |mut recv: UnboundedReceiver<DownloadWaiting>| async move
+	{
+		while let Some(instance) = recv.next().await
+		{
+			//Count down locally, yt-dlp prints nothing while it sleeps
+			for remaining in (1..=instance.seconds).rev()
+			{
+				match *wst.current()
+				{
+					DownloadStatus::Downloading | DownloadStatus::Waiting(_) => wst.set(DownloadStatus::Waiting(remaining)),
+					_ => break,
+				}
+				tokio::time::sleep(Duration::from_secs(1)).await;
+			}
+			
+			if let DownloadStatus::Waiting(_) = *wst.current()
+			{
+				wst.set(DownloadStatus::Downloading);
+			}
+		}
+	});
+	
+	let scp = state.childPid.clone();
+	let started = use_coroutine(cx, |mut recv: UnboundedReceiver<DownloadStarted>| async move
+	{
+		while let Some(instance) = recv.next().await
+		{
+			*scp.write() = instance.pid;
+		}
+	});
+	
+	let vp = state.prompt.clone();
+	let prompt = use_coroutine(cx, |mut recv: UnboundedReceiver<DownloadPrompt>| async move
+	{
+		while let Some(instance) = recv.next().await
+		{
+			vp.set(Some(instance));
+		}
+	});
+	
+	let dst = state.status.clone();
+	let fr = state.failure.clone();
+	let sp = state.prompt.clone();
+	let ht = state.title.clone();
+	let hu = url.to_owned();
+	let cp = state.childPid.clone();
+	let ra = state.retryAttempt.clone();
+	let rd = state.retryDue.clone();
+	let rg = state.retryGeneration.clone();
+	let rp = state.retryPolicy.clone();
+	let sl = state.savedLocation.clone();
+	let sid = state.itemsDownloaded.clone();
+	let stopped = use_coroutine(cx, |mut recv: UnboundedReceiver<DownloadStopped>| async move
+	{
+		while let Some(instance) = recv.next().await
+		{
+			sp.set(None);
+			*cp.write() = None;
+			
+			//Transient failures are retried after a backoff instead of failing outright
+			let attempt = *ra.read() + 1;
+			let delay = match (instance.success, &instance.error)
+			{
+				(false, Some(error)) => rp.read().delay(error, attempt),
+				_ => None,
+			};
+			fr.set(instance.error);
+			
+			if let Some(seconds) = delay
+			{
+				let maxAttempts = rp.read().maxAttempts;
+				//Any download started during the countdown replaces the retry
+				let generation = *rg.read();
+				for remaining in (1..=seconds).rev()
+				{
+					if *rg.read() != generation
+					{
+						break;
+					}
+					
+					match *dst.current()
+					{
+						DownloadStatus::Downloading | DownloadStatus::Retrying { .. } | DownloadStatus::Waiting(_) => dst.set(DownloadStatus::Retrying { attempt, maxAttempts, seconds: remaining }),
+						_ => break,
+					}
+					tokio::time::sleep(Duration::from_secs(1)).await;
+				}
+				
+				//Halting during the countdown cancels the retry
+				if let (DownloadStatus::Retrying { .. }, true) = (dst.current().as_ref(), *rg.read() == generation)
+				{
+					*ra.write() = attempt;
+					dst.set(DownloadStatus::Downloading);
+					rd.set(true);
+				}
+				continue;
+			}
+			
+			//A skipped video exits successfully, keep the reason it was skipped unless something else was downloaded
+			let status = match (dst.current().as_ref(), instance.success)
+			{
+				(DownloadStatus::Skipped(reason), true) if !*sid.read() => DownloadStatus::Skipped(reason.to_owned()),
+				(_, true) => DownloadStatus::Finished,
+				(_, false) => DownloadStatus::Failed,
+			};
+			
+			if status == DownloadStatus::Finished
+			{
+				sl.set(instance.location);
+			}
+			
+			recordHistory(HistoryRecord::new(hu.to_owned(), ht.current().to_string(), status.to_string()));
+			dst.set(status);
+		}
+	});
+	
+	return DownloadEvents
+	{
+		chapter: chapter.clone(),
+		progress: progress.clone(),
+		prompt: prompt.clone(),
+		reset: reset.clone(),
+		skipped: skipped.clone(),
+		started: started.clone(),
+		stopped: stopped.clone(),
+		title: title.clone(),
+		waiting: waiting.clone(),
+	};
+}
+
+// --------------------------------------------------
+
+/// Start the entry once nothing holds it back: a nearly full output disk, the
+/// schedule or the Max Concurrent Downloads. Gives up its slot once yt-dlp
+/// has stopped, so that the next queued entry can start.
+pub fn useDownloadQueue(cx: &ScopeState, control: &DownloadControl, lowDiskSpace: bool)
+{
+	use_effect(cx, (control.holdsSlot(), control.hasSlot()), |_|
+	{
+		to_owned![control];
+		async move
+		{
+			if !control.holdsSlot() && control.hasSlot()
+			{
+				control.downloadSlots.write().remove(&control.id);
+			}
+		}
+	});
+	
+	let scheduleOpen = *control.scheduleOpen.current();
+	use_effect(cx, (control.isWaiting(), lowDiskSpace, scheduleOpen, control.hasFreeSlot()), |(waiting, lowDiskSpace, scheduleOpen, _)|
+	{
+		to_owned![control];
+		async move
+		{
+			if !waiting || !control.isWaiting()
+			{
+				return;
+			}
+			
+			//Slots are looked at again now, another entry may have taken the free one first
+			match (lowDiskSpace, scheduleOpen, control.hasFreeSlot())
+			{
+				(true, _, _) => control.setStatus(DownloadStatus::Held(Reason_LowDiskSpace.to_string())),
+				(false, false, _) => control.setStatus(control.scheduledStatus()),
+				(false, true, false) => control.setStatus(DownloadStatus::Queued),
+				(false, true, true) => {
+					control.state.status.set(DownloadStatus::Downloading);
+					control.run();
+				},
+			}
+		}
+	});
+}
+
+/// Keep the shared bandwidth budget up to date with whether the entry is
+/// running, and restart yt-dlp at its new share once the others have settled.
+pub fn useBandwidthShare(cx: &ScopeState, control: &DownloadControl)
+{
+	use_effect(cx, (control.isActive().then(|| control.ownRate()),), |_|
+	{
+		to_owned![control];
+		async move
+		{
+			let registration = control.isActive().then(|| control.ownRate());
+			if control.activeDownloads.read().get(&control.id).copied() != registration
+			{
+				match registration
+				{
+					Some(rate) => control.activeDownloads.write().insert(control.id, rate),
+					None => control.activeDownloads.write().remove(&control.id),
+				};
+			}
+		}
+	});
+	
+	//yt-dlp cannot change its rate while it runs, so it is restarted to continue at the new one
+	let target = control.targetLimitRate();
+	let applied = control.state.appliedRate.read().to_owned();
+	use_effect(cx, (control.isActive(), target, applied), |(active, target, applied)|
+	{
+		to_owned![control];
+		async move
+		{
+			if !active || target == applied
+			{
+				return;
+			}
+			
+			tokio::time::sleep(Duration::from_secs(RateSettleDelay)).await;
+			let target = control.targetLimitRate();
+			if control.isActive() && target != *control.state.appliedRate.read()
+			{
+				debug!("Restarting to change the rate limit from '{}' to '{}'", control.state.appliedRate.read(), target);
+				control.abort();
+				*control.state.continuePartial.write() = true;
+				control.run();
+			}
+		}
+	});
+}
+
+/// Pause while the output disk is nearly full and resume once space is freed,
+/// unless resumed by hand in between.
+pub fn useDiskSpacePause(cx: &ScopeState, control: &DownloadControl, lowDiskSpace: bool)
+{
+	let pausedForSpace = *control.state.pausedForSpace.read();
+	use_effect(cx, (lowDiskSpace, control.isActive(), pausedForSpace), |(lowDiskSpace, _, _)|
+	{
+		to_owned![control];
+		async move
+		{
+			let pausedForSpace = *control.state.pausedForSpace.read();
+			if lowDiskSpace && control.isActive() && !pausedForSpace
+			{
+				debug!("Pausing, the output disk is nearly full");
+				*control.state.pausedForSpace.write() = true;
+				control.pause();
+			}
+			else if !lowDiskSpace && pausedForSpace
+			{
+				*control.state.pausedForSpace.write() = false;
+				if *control.state.status.current() == DownloadStatus::Paused
+				{
+					control.resume();
+				}
+			}
+		}
+	});
+}
+
+/// Stop a download which stays paused too long, it continues from its partial
+/// files on resume.
+pub fn usePauseTimeout(cx: &ScopeState, control: &DownloadControl)
+{
+	let paused = *control.state.status.current() == DownloadStatus::Paused;
+	let generation = *control.state.pauseGeneration.read();
+	use_effect(cx, (paused, generation), |(paused, generation)|
+	{
+		to_owned![control];
+		async move
+		{
+			let timeout = control.downloaderOptions.read().pauseTimeout;
+			if !paused || timeout == 0
+			{
+				return;
+			}
+			
+			tokio::time::sleep(Duration::from_secs(timeout)).await;
+			if *control.state.status.current() == DownloadStatus::Paused
+				&& *control.state.pauseGeneration.read() == generation
+				&& control.state.process.current().is_some()
+			{
+				debug!("Pause timed out after {} seconds, stopping the download", timeout);
+				control.stopPaused();
+			}
+		}
+	});
+}
+
+/// Start the retry once the countdown in the stopped coroutine has run out.
+pub fn useAutoRetry(cx: &ScopeState, control: &DownloadControl)
+{
+	use_effect(cx, (*control.state.retryDue.current(),), |(due,)|
+	{
+		to_owned![control];
+		async move
+		{
+			if due
+			{
+				control.state.retryDue.set(false);
+				control.abort();
+				control.run();
+			}
+		}
+	});
+}
@@ -0,0 +1,210 @@
+#![allow(non_snake_case, non_upper_case_globals)]
+#![cfg_attr(debug_assertions, allow(dead_code))]
+
+use dioxus::prelude::*;
+use crate::download::{MatchComparison, MatchCondition, MatchField, MatchFieldKind, MatchFilter};
+
+/// The editable form of a condition. The value is kept as raw text so that
+/// partially typed values are not lost while they are still invalid.
+#[derive(Clone, Debug, PartialEq)]
+struct ConditionRow
+{
+	field: MatchField,
+	comparison: MatchComparison,
+	value: String,
+}
+
+impl ConditionRow
+{
+	fn build(&self) -> Result<MatchCondition, String>
+	{
+		return MatchCondition::build(self.field, self.comparison, self.value.to_owned());
+	}
+}
+
+impl From<&MatchCondition> for ConditionRow
+{
+	fn from(condition: &MatchCondition) -> Self
+	{
+		return match condition
+		{
+			MatchCondition::Flag(field, true) => Self { field: *field, comparison: MatchComparison::IsTrue, value: String::default() },
+			MatchCondition::Flag(field, false) => Self { field: *field, comparison: MatchComparison::IsFalse, value: String::default() },
+			MatchCondition::Numeric(field, comparison, n) => Self { field: *field, comparison: *comparison, value: n.to_string() },
+			MatchCondition::Text(field, comparison, text) => Self { field: *field, comparison: *comparison, value: text.to_owned() },
+		};
+	}
+}
+
+/// What a single row needs to render: the field and comparison options,
+/// with the current selections flagged, and the validation error if any.
+struct ConditionView
+{
+	fields: Vec<(String, bool)>,
+	comparisons: Vec<(String, bool)>,
+	value: String,
+	needsValue: bool,
+	error: String,
+}
+
+impl From<&ConditionRow> for ConditionView
+{
+	fn from(row: &ConditionRow) -> Self
+	{
+		return Self
+		{
+			fields: MatchField::All.iter()
+				.map(|f| (f.key().to_string(), *f == row.field))
+				.collect(),
+			comparisons: MatchComparison::forKind(row.field.kind())
+				.iter()
+				.map(|c| (c.to_string(), *c == row.comparison))
+				.collect(),
+			value: row.value.to_owned(),
+			needsValue: row.field.kind() != MatchFieldKind::Flag,
+			error: row.build().err().unwrap_or_default(),
+		};
+	}
+}
+
+/// Builds a `--match-filters` expression out of typed conditions, all of
+/// which must hold for a video to be downloaded.
+#[inline_props]
+pub fn MatchFilterEditor<'a>(cx: Scope, filter: MatchFilter, onChange: EventHandler<'a, MatchFilter>) -> Element<'a>
+{
+	let rows = use_ref(cx, || filter.conditions.iter()
+		.map(ConditionRow::from)
+		.collect::<Vec<ConditionRow>>());
+	
+	//Only pass the filter on once every condition is valid
+	let update = move ||
+	{
+		let built = rows.read()
+			.iter()
+			.map(|row| row.build())
+			.collect::<Result<Vec<MatchCondition>, String>>();
+		
+		if let Ok(conditions) = built
+		{
+			onChange.call(MatchFilter { conditions });
+		}
+	};
+	
+	let views = rows.read()
+		.iter()
+		.map(ConditionView::from)
+		.collect::<Vec<ConditionView>>();
+	
+	let expression = filter.toExpression();
+	
+	return cx.render(rsx!
+	{
+		div
+		{
+			class: "matchFilterEditor",
+			
+			for (i, ConditionView { fields, comparisons, value, needsValue, error }) in views.into_iter().enumerate()
+			{
+				div
+				{
+					key: "{i}",
+					class: "inputRow conditionRow",
+					
+					select
+					{
+						onchange: move |evt: FormEvent| {
+							if let Some(field) = evt.value.parse::<usize>().ok().and_then(|f| MatchField::All.get(f))
+							{
+								{
+									let mut list = rows.write();
+									list[i].field = *field;
+									let allowed = MatchComparison::forKind(field.kind());
+									if !allowed.contains(&list[i].comparison)
+									{
+										list[i].comparison = allowed[0];
+									}
+								}
+								update();
+							}
+						},
+						
+						for (f, (name, selected)) in fields.into_iter().enumerate()
+						{
+							option { key: "{f}", value: "{f}", selected: "{selected}", "{name}" }
+						}
+					}
+					
+					select
+					{
+						onchange: move |evt: FormEvent| {
+							if let Ok(c) = evt.value.parse::<usize>()
+							{
+								{
+									let mut list = rows.write();
+									let allowed = MatchComparison::forKind(list[i].field.kind());
+									if let Some(comparison) = allowed.get(c)
+									{
+										list[i].comparison = *comparison;
+									}
+								}
+								update();
+							}
+						},
+						
+						for (c, (name, selected)) in comparisons.into_iter().enumerate()
+						{
+							option { key: "{c}", value: "{c}", selected: "{selected}", "{name}" }
+						}
+					}
+					
+					needsValue.then(|| rsx!
+					{
+						input
+						{
+							r#type: "text",
+							value: "{value}",
+							oninput: move |evt: FormEvent| {
+								rows.write()[i].value = evt.value.to_owned();
+								update();
+							}
+						}
+					})
+					
+					button
+					{
+						onclick: move |_| {
+							rows.write().remove(i);
+							update();
+						},
+						"Remove"
+					}
+					
+					(!error.is_empty()).then(|| rsx!
+					{
+						div { class: "inputError", title: "{error}", "{error}" }
+					})
+				}
+			}
+			
+			div
+			{
+				class: "row",
+				
+				h6 { "{expression}" }
+				
+				button
+				{
+					onclick: move |_| {
+						rows.write().push(ConditionRow
+						{
+							field: MatchField::Duration,
+							comparison: MatchComparison::GreaterThan,
+							value: String::default(),
+						});
+					},
+					"Add Condition"
+				}
+			}
+		}
+	});
+}
@@ -0,0 +1,159 @@
+#![allow(non_snake_case, non_upper_case_globals)]
+#![cfg_attr(debug_assertions, allow(dead_code))]
+
+use dioxus::prelude::*;
+use fermi::use_read;
+use std::collections::BTreeMap;
+use crate::components::progress::DownloadElement;
+use crate::download::{fetchPlaylist, PlaylistEntry};
+use crate::state::Binary;
+
+/// A collapsible group of download entries created by flat-expanding a
+/// playlist or channel. URLs which turn out not to be playlists fall back
+/// to a single entry.
+#[inline_props]
+pub fn PlaylistGroupElement<'a>(cx: Scope, indexKey: usize, playlistUrl: String, onRemove: EventHandler<'a, usize>) -> Element<'a>
+{
+	let binary = use_read(cx, Binary);
+	
+	let collapsed = use_state(cx, || false);
+	let entries = use_ref(cx, || None::<BTreeMap<usize, PlaylistEntry>>);
+	let title = use_state(cx, || playlistUrl.to_owned());
+	
+	use_future(cx, (), |_|
+	{
+		to_owned![binary, entries, playlistUrl, title];
+		async move
+		{
+			let expanded = match fetchPlaylist(binary.into(), playlistUrl.to_owned()).await
+			{
+				Some(p) => {
+					if let Some(t) = p.title.filter(|t| !t.is_empty())
+					{
+						title.set(t);
+					}
+					
+					p.entries.into_iter()
+						.filter(|entry| !entry.url.is_empty())
+						.enumerate()
+						.collect()
+				},
+				None => BTreeMap::<usize, PlaylistEntry>::default(),
+			};
+			
+			*entries.write() = Some(expanded);
+		}
+	});
+	
+	let children = entries.read()
+		.as_ref()
+		.map(|map| map.iter()
+			.map(|(k, entry)| (*k, entry.url.to_owned()))
+			.collect::<Vec<(usize, String)>>());
+	
+	let collapseText = match **collapsed
+	{
+		true => "Expand",
+		false => "Collapse",
+	};
+	
+	//Hide collapsed entries rather than unmounting them so their downloads keep running
+	let entriesClass = match **collapsed
+	{
+		true => "groupEntries collapsed",
+		false => "groupEntries",
+	};
+	
+	return cx.render(rsx!
+	{
+		match children
+		{
+			None => rsx!
+			{
+				div
+				{
+					class: "download",
+					h4 { "{playlistUrl}" }
+					h6 { class: "status", "Expanding playlist..." }
+				}
+			},
+			
+			Some(list) if list.is_empty() => rsx!
+			{
+				DownloadElement
+				{
+					indexKey: *indexKey,
+					videoUrl: playlistUrl.to_owned(),
+					onRemove: move |key: usize| onRemove.call(key)
+				}
+			},
+			
+			Some(list) => {
+				let count = list.len();
+				rsx!
+				{
+					div
+					{
+						class: "downloadGroup",
+						
+						div
+						{
+							class: "groupHeader",
+							
+							h4 { "{title}" }
+							h6 { "{count} items" }
+							
+							div
+							{
+								class: "buttonRow",
+								
+								button
+								{
+									class: "collapseButton",
+									onclick: move |_| collapsed.set(!collapsed),
+									"{collapseText}"
+								}
+								
+								button
+								{
+									class: "removeButton",
+									onclick: move |_| onRemove.call(*indexKey),
+									"Remove"
+								}
+							}
+						}
+						
+						div
+						{
+							class: "{entriesClass}",
+							
+							for (key, url) in list.into_iter()
+							{
+								DownloadElement
+								{
+									key: "{key}",
+									indexKey: key,
+									videoUrl: url,
+									onRemove: move |k: usize| {
+										let mut empty = false;
+										if let Some(map) = entries.write().as_mut()
+										{
+											map.remove(&k);
+											empty = map.is_empty();
+										}
+										
+										//Removing the last item removes the whole group
+										if empty
+										{
+											onRemove.call(*indexKey);
+										}
+									}
+								}
+							}
+						}
+					}
+				}
+			},
+		}
+	});
+}
@@ -0,0 +1,132 @@
+#![allow(non_snake_case, non_upper_case_globals)]
+#![cfg_attr(debug_assertions, allow(dead_code))]
+
+use dioxus::prelude::*;
+use crate::download::{validateHeaders, HttpHeader, Header_Referer, Header_UserAgent, UserAgentPresets};
+
+/// Edits the custom HTTP headers sent with every request, with presets for
+/// common user agents. Duplicate and malformed headers are flagged on their
+/// row and left out of the arguments.
+#[inline_props]
+pub fn HeadersEditor<'a>(cx: Scope, headers: Vec<HttpHeader>, onChange: EventHandler<'a, Vec<HttpHeader>>) -> Element<'a>
+{
+	let rows = headers.iter()
+		.zip(validateHeaders(headers))
+		.map(|(header, error)| (header.name.to_owned(), header.value.to_owned(), error))
+		.collect::<Vec<(String, String, String)>>();
+	
+	let hasReferer = headers.iter().any(|h| h.name.trim().eq_ignore_ascii_case(Header_Referer));
+	
+	//Replace the user agent rather than adding a duplicate
+	let setUserAgent = move |agent: &str|
+	{
+		let mut list = headers.to_owned();
+		match list.iter_mut().find(|h| h.name.trim().eq_ignore_ascii_case(Header_UserAgent))
+		{
+			Some(header) => header.value = agent.to_string(),
+			None => list.push(HttpHeader::new(Header_UserAgent, agent)),
+		}
+		onChange.call(list);
+	};
+	
+	return cx.render(rsx!
+	{
+		div
+		{
+			class: "headersEditor",
+			
+			for (i, (name, value, error)) in rows.into_iter().enumerate()
+			{
+				div
+				{
+					key: "{i}",
+					class: "inputRow headerRow",
+					
+					input
+					{
+						r#type: "text",
+						placeholder: "Name",
+						value: "{name}",
+						oninput: move |evt: FormEvent| {
+							let mut list = headers.to_owned();
+							list[i].name = evt.value.to_owned();
+							onChange.call(list);
+						}
+					}
+					
+					input
+					{
+						r#type: "text",
+						placeholder: "Value",
+						value: "{value}",
+						oninput: move |evt: FormEvent| {
+							let mut list = headers.to_owned();
+							list[i].value = evt.value.to_owned();
+							onChange.call(list);
+						}
+					}
+					
+					button
+					{
+						onclick: move |_| {
+							let mut list = headers.to_owned();
+							list.remove(i);
+							onChange.call(list);
+						},
+						"Remove"
+					}
+					
+					(!error.is_empty()).then(|| rsx!
+					{
+						div { class: "inputError", title: "{error}", "{error}" }
+					})
+				}
+			}
+			
+			div
+			{
+				class: "row",
+				
+				select
+				{
+					title: "Set the User-Agent header to a common browser",
+					onchange: move |evt: FormEvent| {
+						if let Some((_, agent)) = evt.value.parse::<usize>().ok().and_then(|p| UserAgentPresets.get(p))
+						{
+							setUserAgent(agent);
+						}
+					},
+					
+					option { value: "", selected: "true", "User Agent Presets" }
+					for (p, (name, _)) in UserAgentPresets.iter().enumerate()
+					{
+						option { key: "{p}", value: "{p}", "{name}" }
+					}
+				}
+				
+				(!hasReferer).then(|| rsx!
+				{
+					button
+					{
+						onclick: move |_| {
+							let mut list = headers.to_owned();
+							list.push(HttpHeader::new(Header_Referer, ""));
+							onChange.call(list);
+						},
+						"Add Referer"
+					}
+				})
+				
+				button
+				{
+					onclick: move |_| {
+						let mut list = headers.to_owned();
+						list.push(HttpHeader::default());
+						onChange.call(list);
+					},
+					"Add Header"
+				}
+			}
+		}
+	});
+}
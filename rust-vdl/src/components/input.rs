@@ -49,7 +49,7 @@ pub fn InputRow<'a>(cx: Scope,
 #[inline_props]
 pub fn LabelInputRow<'a>(cx: Scope,
 	label: String, name: String, value: String, onInput: EventHandler<'a, FormEvent>,
-	class: Option<String>, placeholder: Option<String>, title: Option<String>,
+	class: Option<String>, error: Option<String>, placeholder: Option<String>, title: Option<String>,
 ) -> Element<'a>
 {
 	let c = match class
@@ -58,6 +58,12 @@ pub fn LabelInputRow<'a>(cx: Scope,
 		Some(c) => c.into(),
 	};
 	
+	let e = match error
+	{
+		None => String::default(),
+		Some(e) => e.into(),
+	};
+	
 	let p = match placeholder
 	{
 		None => String::default(),
@@ -86,6 +92,11 @@ pub fn LabelInputRow<'a>(cx: Scope,
 				value: "{value}",
 				oninput: move |evt| onInput.call(evt)
 			}
+			
+			(!e.is_empty()).then(|| rsx!
+			{
+				div { class: "inputError", title: "{e}", "{e}" }
+			})
 		}
 	});
 }
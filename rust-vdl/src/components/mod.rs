@@ -2,11 +2,36 @@
 #![cfg_attr(debug_assertions, allow(dead_code))]
 
 mod app;
+mod archive;
+mod cookies;
+mod downloader;
+mod filter;
+mod group;
+mod headers;
 mod input;
 mod options;
+mod playlist;
 mod progress;
+mod prompt;
+mod secrets;
+mod schedule;
+mod sections;
+mod subscriptions;
+mod template;
 
 pub use app::App;
+pub use archive::ArchiveEditor;
+pub use cookies::BrowserCookiesEditor;
+pub use filter::MatchFilterEditor;
+pub use group::PlaylistGroupElement;
+pub use headers::HeadersEditor;
 pub use input::{InputRow, LabelInputRow, ToggleRow};
 pub use options::Options;
+pub use playlist::PlaylistChecklist;
 pub use progress::DownloadElement;
+pub use prompt::PromptDialog;
+pub use secrets::{CredentialsPrompt, SecretsEditor};
+pub use schedule::ScheduleEditor;
+pub use sections::SectionsEditor;
+pub use subscriptions::{useSubscriptionChecks, SubscriptionsView};
+pub use template::TemplateInputRow;
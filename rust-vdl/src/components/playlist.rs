@@ -0,0 +1,106 @@
+#![allow(non_snake_case, non_upper_case_globals)]
+#![cfg_attr(debug_assertions, allow(dead_code))]
+
+use dioxus::prelude::*;
+use crate::download::{parsePlaylistItems, playlistItemsFromIndices, PlaylistEntry};
+
+/// A checklist of the entries of a flat-expanded playlist which generates
+/// the matching `--playlist-items` specification.
+#[inline_props]
+pub fn PlaylistChecklist<'a>(cx: Scope,
+	entries: Vec<PlaylistEntry>, items: String, onApply: EventHandler<'a, String>,
+) -> Element<'a>
+{
+	let checked = use_ref(cx, || {
+		let count = entries.len();
+		match parsePlaylistItems(items.to_owned())
+		{
+			Ok(list) if !items.is_empty() => (1..=count)
+				.map(|i| list.iter().any(|item| item.contains(i, count)))
+				.collect::<Vec<bool>>(),
+			_ => vec![true; count],
+		}
+	});
+	
+	let states = checked.read().clone();
+	let selectedCount = states.iter().filter(|c| **c).count();
+	let total = entries.len();
+	let noneSelected = selectedCount == 0;
+	let labels = entries.iter()
+		.enumerate()
+		.map(|(i, entry)| format!("{}. {}", i + 1, entry.displayTitle()))
+		.collect::<Vec<String>>();
+	
+	return cx.render(rsx!
+	{
+		div
+		{
+			class: "playlistChecklist",
+			
+			h5 { "{selectedCount} of {total} selected" }
+			
+			ul
+			{
+				for (i, (label, isChecked)) in labels.iter().zip(states.iter()).enumerate()
+				{
+					li
+					{
+						key: "{i}",
+						
+						input
+						{
+							r#type: "checkbox",
+							id: "playlistEntry{i}",
+							checked: "{isChecked}",
+							oninput: move |evt: FormEvent| {
+								if let Ok(val) = evt.value.parse::<bool>()
+								{
+									checked.write()[i] = val;
+								}
+							}
+						}
+						
+						label { r#for: "playlistEntry{i}", "{label}" }
+					}
+				}
+			}
+			
+			div
+			{
+				class: "row",
+				
+				button
+				{
+					onclick: move |_| checked.write().iter_mut().for_each(|c| *c = true),
+					"Select All"
+				}
+				
+				button
+				{
+					onclick: move |_| checked.write().iter_mut().for_each(|c| *c = false),
+					"Select None"
+				}
+				
+				button
+				{
+					disabled: "{noneSelected}",
+					onclick: move |_| {
+						let list = checked.read();
+						//Everything selected is the same as no selection at all
+						let spec = match list.iter().all(|c| *c)
+						{
+							true => String::default(),
+							false => playlistItemsFromIndices(list.iter()
+								.enumerate()
+								.filter(|(_, c)| **c)
+								.map(|(i, _)| i + 1)
+								.collect()),
+						};
+						onApply.call(spec);
+					},
+					"Apply"
+				}
+			}
+		}
+	});
+}
@@ -2,121 +2,108 @@
 #![cfg_attr(debug_assertions, allow(dead_code))]
 
 use dioxus::prelude::*;
-use fermi::{use_atom_ref, use_read};
-use futures::StreamExt;
-use crate::download::{DownloadProgress, DownloadReset, DownloadStopped, DownloadTitle, VideoDownloader};
-use crate::state::{Binary, DownloaderOptions, UrlList};
+use fermi::{use_atom_ref, use_read, use_set};
+use crate::components::downloader::{useAutoRetry, useBandwidthShare, useDiskSpacePause, useDownloadControl, useDownloadQueue, usePauseTimeout};
+use crate::components::playlist::PlaylistChecklist;
+use crate::components::prompt::PromptDialog;
+use crate::components::secrets::CredentialsPrompt;
+use crate::components::sections::SectionsEditor;
+use crate::download::{fetchMetadata, fetchPlaylist, ByteSize, CanPause, DownloadProgress, DownloadSection, DownloadStatus,
+	PlaylistMetadata, SubscriptionProfile};
+use crate::state::{Binary, DownloaderOptions, LowDiskSpace, ScheduleOpen, ShowOptions};
 
 #[inline_props]
-pub fn DownloadElement(cx: Scope, indexKey: usize, videoUrl: String) -> Element
+pub fn DownloadElement<'a>(cx: Scope, indexKey: usize, videoUrl: String, onRemove: EventHandler<'a, usize>, profile: Option<SubscriptionProfile>) -> Element<'a>
 {
 	let binary = use_read(cx, Binary);
 	let downloaderOptions = use_atom_ref(cx, DownloaderOptions);
-	let urlList = use_atom_ref(cx, UrlList);
+	let lowDiskSpace = use_read(cx, LowDiskSpace).is_some();
+	let scheduleOpen = use_read(cx, ScheduleOpen);
+	let setShowOptions = use_set(cx, ShowOptions);
 	
-	let downloadProcess = use_state(cx, || None);
-	let downloadStopped = use_state(cx, || false);
-	let playlistCurrent = use_state(cx, || 0 as usize);
-	let playlistMax = use_state(cx, || 0 as usize);
-	let progressBars = use_ref(cx, || Vec::<(String, DownloadProgress)>::default());
-	let shouldReset = use_ref(cx, || false);
-	let title = use_state(cx, || videoUrl.to_owned());
+	let control = useDownloadControl(cx, videoUrl, profile);
+	useDownloadQueue(cx, control, lowDiskSpace);
+	useBandwidthShare(cx, control);
+	useDiskSpacePause(cx, control, lowDiskSpace);
+	usePauseTimeout(cx, control);
+	useAutoRetry(cx, control);
 	
-	let vt = title.clone();
-	let titleCoroutine = use_coroutine(cx, |mut recv: UnboundedReceiver<DownloadTitle>| async move
-	{
-		while let Some(instance) = recv.next().await
-		{
-			if !instance.title.is_empty() && !vt.eq(&instance.title)
-			{
-				vt.set(instance.title.to_owned());
-			}
-		}
-	});
+	let downloadId = control.id;
+	let state = &control.state;
+	let chapterFiles = &state.chapterFiles;
+	let downloadStatus = state.status.current();
+	let failure = state.failure.current();
+	let metadata = &state.metadata;
+	let playlistCurrent = *state.playlistCurrent.current();
+	let playlistItems = &state.playlistItems;
+	let playlistMax = *state.playlistMax.current();
+	let progressBars = &state.progressBars;
+	let prompt = state.prompt.current();
+	let rateOverride = state.rateOverride.current();
+	let savedLocation = state.savedLocation.current();
+	let sections = &state.sections;
+	let spaceWarning = state.spaceWarning.current();
+	let title = state.title.current();
+	
+	let playlist = use_state(cx, || None::<PlaylistMetadata>);
+	let showPlaylist = use_state(cx, || false);
+	let showRate = use_state(cx, || false);
+	let showSections = use_state(cx, || false);
+	
+	//Every bar being complete while waiting only means the next item hasn't started yet
+	let finished = matches!(downloadStatus.as_ref(), DownloadStatus::Finished | DownloadStatus::Skipped(_))
+					|| (!matches!(downloadStatus.as_ref(), DownloadStatus::Paused | DownloadStatus::Retrying { .. } | DownloadStatus::Waiting(_))
+						&& !progressBars.read().is_empty()
+						&& progressBars.read()
+							.iter()
+							.all(|(_, prog)| prog.percentComplete == "100%"));
 	
-	let dpr = progressBars.clone();
-	let sr1 = shouldReset.clone();
-	let progressCoroutine = use_coroutine(cx, |mut recv: UnboundedReceiver<DownloadProgress>| async move
+	let btnString = match downloadStatus.as_ref()
 	{
-		while let Some(instance) = recv.next().await
-		{
-			let mut list = dpr.write();
-			
-			let mut resetFlag = sr1.write();
-			if *resetFlag
-			{
-				list.clear();
-				*resetFlag = false;
-			}
-			
-			if let Some((_, prog)) = list.iter_mut().find(|(label, _)| label == &instance.label)
-			{
-				if prog.percentComplete != "100%"
-				{
-					prog.update(instance.to_owned());
-				}
-			}
-			else
-			{
-				list.push((instance.label.to_owned(), instance.to_owned()));
-			}
-		}
-	});
+		DownloadStatus::Downloading | DownloadStatus::Retrying { .. } | DownloadStatus::Waiting(_) => "Halt",
+		DownloadStatus::Failed => "Retry",
+		DownloadStatus::Paused => "Resume",
+		DownloadStatus::Held(_) | DownloadStatus::Queued | DownloadStatus::Scheduled(_) => "Start Now",
+		_ => "Start",
+	};
 	
-	let pc = playlistCurrent.clone();
-	let pm = playlistMax.clone();
-	let sr2 = shouldReset.clone();
-	let resetCoroutine = use_coroutine(cx, |mut recv: UnboundedReceiver<DownloadReset>| async move
+	let statusText = match (finished, downloadStatus.as_ref())
 	{
-		while let Some(instance) = recv.next().await
-		{
-			*sr2.write() = true;
-			pc.set(instance.playlistCurrent);
-			pm.set(instance.playlistMax);
-		}
-	});
+		(_, DownloadStatus::Skipped(_)) | (false, _) => downloadStatus.as_ref().to_string(),
+		(true, _) => DownloadStatus::Finished.to_string(),
+	};
 	
-	let dst = downloadStopped.clone();
-	let stoppedCoroutine = use_coroutine(cx, |mut recv: UnboundedReceiver<DownloadStopped>| async move
+	let playlistText = match playlistCurrent > 0 && playlistMax > 0
 	{
-		while let Some(_) = recv.next().await
-		{
-			dst.set(true);
-		}
-	});
+		true => format!("[{} of {}]: ", playlistCurrent, playlistMax),
+		false => "".to_string(),
+	};
+	
+	let isPlaylist = downloaderOptions.read().downloadPlaylist;
 	
-	startDownloader(cx, ||
+	let canPause = control.isActive();
+	let pauseTitle = match CanPause
 	{
-		to_owned![binary, videoUrl, progressCoroutine, resetCoroutine, stoppedCoroutine, titleCoroutine];
-		let dlopts = downloaderOptions.read().clone();
-		let handle = tokio::task::spawn(async move {
-			let mut vdl = VideoDownloader::new(binary.into(), dlopts.to_owned());
-			vdl.download(videoUrl.into(),
-				Box::new(move |dp| progressCoroutine.send(dp)),
-				Box::new(move |dr| resetCoroutine.send(dr)),
-				Box::new(move |ds| stoppedCoroutine.send(ds)),
-				Box::new(move |dt| titleCoroutine.send(dt))
-			).await;
-		});
-		
-		downloadProcess.set(Some(handle));
-	});
+		true => "Suspend yt-dlp, keeping its place",
+		false => "Stop yt-dlp, continuing from the partial files on resume",
+	};
 	
-	let finished = !progressBars.read().is_empty()
-						&& progressBars.read()
-							.iter()
-							.all(|(_, prog)| prog.percentComplete == "100%");
+	let rateError = match rateOverride.trim().is_empty()
+	{
+		true => String::default(),
+		false => ByteSize::parse(rateOverride.to_string()).err().unwrap_or_default(),
+	};
 	
-	let btnString = match *downloadStopped.get()
+	let ratePlaceholder = match downloaderOptions.read().schedule.budget(*scheduleOpen, downloaderOptions.read().bandwidthLimit.to_owned())
 	{
-		true => "Start",
-		false => "Halt",
+		None => "Unlimited",
+		Some(_) => "Shared",
 	};
 	
-	let playlistText = match *playlistCurrent.get() > 0 && *playlistMax.get() > 0
+	let locationText = match downloaderOptions.read().paths.usesTemp()
 	{
-		true => format!("[{} of {}]: ", playlistCurrent, playlistMax),
-		false => "".to_string(),
+		true => "Moved to",
+		false => "Saved to",
 	};
 	
 	let removeClass = match !finished
@@ -132,6 +119,109 @@ pub fn DownloadElement(cx: Scope, indexKey: usize, videoUrl: String) -> Element
 			class: "download",
 			
 			h4 { "{playlistText}{title}" }
+			h6 { class: "status", "{statusText}" }
+			
+			savedLocation.as_ref().as_ref().filter(|_| finished).map(|path| rsx!
+			{
+				h6 { class: "savedLocation", title: "{path}", "{locationText} {path}" }
+			})
+			
+			spaceWarning.as_ref().as_ref().map(|warning| rsx!
+			{
+				h6 { class: "spaceWarning", "{warning}" }
+			})
+			
+			failure.as_ref().as_ref().map(|error| rsx!
+			{
+				div
+				{
+					class: "row failureReason",
+					
+					h6 { "{error}" }
+					
+					error.hint().map(|hint| rsx!
+					{
+						h6 { class: "failureHint", "{hint}" }
+					})
+					
+					//Point at the settings which can get around the error
+					error.hasSettings().then(|| rsx!
+					{
+						button
+						{
+							onclick: move |_| setShowOptions(true),
+							"Options"
+						}
+					})
+				}
+			})
+			
+			prompt.as_ref().as_ref().map(|p| rsx!
+			{
+				PromptDialog
+				{
+					text: p.text.to_owned(),
+					secret: p.secret,
+					onAnswer: move |answer: String| {
+						if let Some(sender) = state.promptAnswers.read().as_ref()
+						{
+							let _ = sender.unbounded_send(answer);
+						}
+						state.prompt.set(None);
+					}
+				}
+			})
+			
+			(*state.showCredentials.current()).then(|| rsx!
+			{
+				CredentialsPrompt
+				{
+					username: downloaderOptions.read().username.to_owned(),
+					onSubmit: move |(password, twoFactor): (String, String)| control.submitCredentials(password, twoFactor)
+				}
+			})
+			
+			if **showSections
+			{
+				rsx!
+				{
+					SectionsEditor
+					{
+						sections: sections.read().clone(),
+						duration: metadata.current().as_ref().and_then(|m| m.durationMillis()),
+						onApply: move |list: Vec<DownloadSection>| {
+							//Restart the download so that only the chosen sections are fetched
+							*sections.write() = list;
+							showSections.set(false);
+							control.restart();
+						}
+					}
+				}
+			}
+			
+			(**showRate).then(|| rsx!
+			{
+				div
+				{
+					class: "row rateOverride",
+					
+					label { r#for: "rate{downloadId}", "Rate Limit" }
+					input
+					{
+						id: "rate{downloadId}",
+						r#type: "text",
+						placeholder: "{ratePlaceholder}",
+						title: "A rate for this download alone, e.g. 500K. Leave empty to take a share of the Bandwidth Limit.",
+						value: "{rateOverride}",
+						oninput: move |evt| state.rateOverride.set(evt.value.to_owned())
+					}
+					
+					(!rateError.is_empty()).then(|| rsx!
+					{
+						div { class: "inputError", title: "{rateError}", "{rateError}" }
+					})
+				}
+			})
 			
 			for (i, (dpl, dp)) in progressBars.read().iter().enumerate()
 			{
@@ -141,6 +231,41 @@ pub fn DownloadElement(cx: Scope, indexKey: usize, videoUrl: String) -> Element
 				}
 			}
 			
+			(!chapterFiles.read().is_empty()).then(|| rsx!
+			{
+				ul
+				{
+					class: "chapterFiles",
+					
+					for chapter in chapterFiles.read().iter()
+					{
+						li { key: "{chapter.number}", "{chapter.number}: {chapter.fileName}" }
+					}
+				}
+			})
+			
+			if **showPlaylist
+			{
+				match playlist.get()
+				{
+					Some(p) => rsx!
+					{
+						PlaylistChecklist
+						{
+							entries: p.entries.to_owned(),
+							items: playlistItems.read().clone(),
+							onApply: move |spec: String| {
+								//Restart the download so that only the chosen items are fetched
+								*playlistItems.write() = spec;
+								showPlaylist.set(false);
+								control.restart();
+							}
+						}
+					},
+					None => rsx!(h5 { "Loading playlist..." }),
+				}
+			}
+			
 			div
 			{
 				class: "buttonRow",
@@ -152,29 +277,11 @@ pub fn DownloadElement(cx: Scope, indexKey: usize, videoUrl: String) -> Element
 						class: "haltResumeButton",
 						
 						onclick: move |_| {
-							match downloadProcess.get()
+							match *state.status.current()
 							{
-								Some(handle) => {
-									handle.abort();
-									downloadProcess.set(None);
-									downloadStopped.set(true);
-								},
-								None => {
-									to_owned![binary, videoUrl, progressCoroutine, resetCoroutine, stoppedCoroutine, titleCoroutine];
-									let dlopts2 = downloaderOptions.read().clone();
-									let handle = tokio::task::spawn(async move {
-										let mut vdl = VideoDownloader::new(binary.into(), dlopts2.to_owned());
-										vdl.download(videoUrl.into(),
-											Box::new(move |dp| progressCoroutine.send(dp)),
-											Box::new(move |dr| resetCoroutine.send(dr)),
-											Box::new(move |ds| stoppedCoroutine.send(ds)),
-											Box::new(move |dt| titleCoroutine.send(dt))
-										).await;
-									});
-									
-									downloadStopped.set(false);
-									downloadProcess.set(Some(handle));
-								},
+								DownloadStatus::Downloading | DownloadStatus::Retrying { .. } | DownloadStatus::Waiting(_) => control.halt(),
+								DownloadStatus::Paused => control.resume(),
+								_ => control.start(),
 							};
 						},
 						
@@ -182,18 +289,80 @@ pub fn DownloadElement(cx: Scope, indexKey: usize, videoUrl: String) -> Element
 					}
 				})
 				
+				canPause.then(|| rsx!
+				{
+					button
+					{
+						class: "pauseButton",
+						title: "{pauseTitle}",
+						
+						onclick: move |_| control.pause(),
+						
+						"Pause"
+					}
+				})
+				
 				button
 				{
-					class: "{removeClass}",
+					class: "sectionsButton",
 					
 					onclick: move |_| {
-						if let Some(handle) = downloadProcess.get()
+						if metadata.current().is_none()
 						{
-							handle.abort();
-							downloadProcess.set(None);
+							to_owned![binary, videoUrl, metadata];
+							cx.spawn(async move {
+								if let Some(m) = fetchMetadata(binary.into(), videoUrl.into()).await
+								{
+									metadata.set(Some(m));
+								}
+							});
 						}
 						
-						urlList.write().remove_entry(indexKey);
+						showSections.set(!showSections);
+					},
+					
+					"Sections"
+				}
+				
+				button
+				{
+					class: "rateButton",
+					onclick: move |_| showRate.set(!showRate),
+					"Rate"
+				}
+				
+				isPlaylist.then(|| rsx!
+				{
+					button
+					{
+						class: "playlistButton",
+						
+						onclick: move |_| {
+							if playlist.get().is_none()
+							{
+								to_owned![binary, videoUrl, playlist];
+								cx.spawn(async move {
+									if let Some(p) = fetchPlaylist(binary.into(), videoUrl.into()).await
+									{
+										playlist.set(Some(p));
+									}
+								});
+							}
+							
+							showPlaylist.set(!showPlaylist);
+						},
+						
+						"Items"
+					}
+				})
+				
+				button
+				{
+					class: "{removeClass}",
+					
+					onclick: move |_| {
+						control.remove();
+						onRemove.call(*indexKey);
 					},
 					
 					"Remove"
@@ -243,7 +412,7 @@ fn DownloadProgressBar(cx: Scope, label: String, progress: DownloadProgress) ->
 				h5 { "{percentDisplay}" }
 			}
 			
-			(!progress.transferRate.is_empty() || !progress.size.is_empty() || !progress.time.is_empty()).then(|| rsx!
+			(!progress.transferRate.is_empty() || !progress.size.is_empty() || !progress.time.is_empty() || !progress.fragmentStatus.is_empty()).then(|| rsx!
 			{
 				div
 					{
@@ -252,21 +421,13 @@ fn DownloadProgressBar(cx: Scope, label: String, progress: DownloadProgress) ->
 						h6 { "Rate: {progress.transferRate}" }
 						h6 { "Size: {progress.size}" }
 						h6 { "Time: {progress.time}" }
+						
+						(!progress.fragmentStatus.is_empty()).then(|| rsx!
+						{
+							h6 { "Fragments: {progress.fragmentStatus}" }
+						})
 					}
 			})
 		}
 	});
 }
-
-// --------------------------------------------------
-
-/// Hook to call a function only once within the given scope.
-fn startDownloader<'a>(cx: Scope<'a, DownloadElementProps>, f: impl FnOnce())
-{
-	let run = cx.use_hook(|| true);
-	if *run
-	{
-		f();
-		*run = false;
-	}
-}
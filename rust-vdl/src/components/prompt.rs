@@ -0,0 +1,48 @@
+#![allow(non_snake_case, non_upper_case_globals)]
+#![cfg_attr(debug_assertions, allow(dead_code))]
+
+use dioxus::prelude::*;
+
+/// Shows a question yt-dlp is waiting on and sends the answer back to it.
+#[inline_props]
+pub fn PromptDialog<'a>(cx: Scope, text: String, secret: bool, onAnswer: EventHandler<'a, String>) -> Element<'a>
+{
+	let answer = use_state(cx, || String::default());
+	
+	let inputType = match secret
+	{
+		true => "password",
+		false => "text",
+	};
+	
+	return cx.render(rsx!
+	{
+		div
+		{
+			class: "promptDialog",
+			
+			h5 { "{text}" }
+			
+			div
+			{
+				class: "row",
+				
+				input
+				{
+					r#type: "{inputType}",
+					value: "{answer}",
+					oninput: move |evt: FormEvent| answer.set(evt.value.to_owned())
+				}
+				
+				button
+				{
+					onclick: move |_| {
+						onAnswer.call(answer.get().to_owned());
+						answer.set(String::default());
+					},
+					"Answer"
+				}
+			}
+		}
+	});
+}
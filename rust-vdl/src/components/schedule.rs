@@ -0,0 +1,113 @@
+#![allow(non_snake_case, non_upper_case_globals)]
+#![cfg_attr(debug_assertions, allow(dead_code))]
+
+use dioxus::prelude::*;
+use crate::download::{ScheduleWindow, Weekdays};
+
+/// Edits the windows of the week downloads are allowed to run in. A window
+/// ending before it starts runs past midnight, e.g. 22:00 to 06:00.
+#[inline_props]
+pub fn ScheduleEditor<'a>(cx: Scope, windows: Vec<ScheduleWindow>, onChange: EventHandler<'a, Vec<ScheduleWindow>>) -> Element<'a>
+{
+	let rows = windows.iter()
+		.map(|window| (
+			window.day,
+			window.start.to_owned(),
+			window.end.to_owned(),
+			window.validate().err().unwrap_or_default(),
+		))
+		.collect::<Vec<(usize, String, String, String)>>();
+	
+	//Start a new window on the day after the last one, for filling in the week
+	let nextDay = windows.last().map_or(0, |w| (w.day + 1) % Weekdays.len());
+	
+	return cx.render(rsx!
+	{
+		div
+		{
+			class: "scheduleEditor",
+			
+			for (i, (day, start, end, error)) in rows.into_iter().enumerate()
+			{
+				div
+				{
+					key: "{i}",
+					class: "inputRow scheduleRow",
+					
+					select
+					{
+						onchange: move |evt: FormEvent| {
+							if let Ok(d) = evt.value.parse::<usize>()
+							{
+								let mut list = windows.to_owned();
+								list[i].day = d;
+								onChange.call(list);
+							}
+						},
+						
+						for (d, name, selected) in Weekdays.iter().enumerate().map(|(d, name)| (d, name, d == day))
+						{
+							option { key: "{d}", value: "{d}", selected: "{selected}", "{name}" }
+						}
+					}
+					
+					input
+					{
+						r#type: "text",
+						placeholder: "HH:MM",
+						title: "When the window opens",
+						value: "{start}",
+						oninput: move |evt: FormEvent| {
+							let mut list = windows.to_owned();
+							list[i].start = evt.value.trim().to_owned();
+							onChange.call(list);
+						}
+					}
+					
+					input
+					{
+						r#type: "text",
+						placeholder: "HH:MM",
+						title: "When the window closes",
+						value: "{end}",
+						oninput: move |evt: FormEvent| {
+							let mut list = windows.to_owned();
+							list[i].end = evt.value.trim().to_owned();
+							onChange.call(list);
+						}
+					}
+					
+					button
+					{
+						onclick: move |_| {
+							let mut list = windows.to_owned();
+							list.remove(i);
+							onChange.call(list);
+						},
+						"Remove"
+					}
+					
+					(!error.is_empty()).then(|| rsx!
+					{
+						div { class: "inputError", title: "{error}", "{error}" }
+					})
+				}
+			}
+			
+			div
+			{
+				class: "row",
+				
+				button
+				{
+					onclick: move |_| {
+						let mut list = windows.to_owned();
+						list.push(ScheduleWindow { day: nextDay, ..Default::default() });
+						onChange.call(list);
+					},
+					"Add Window"
+				}
+			}
+		}
+	});
+}
@@ -0,0 +1,191 @@
+#![allow(non_snake_case, non_upper_case_globals)]
+#![cfg_attr(debug_assertions, allow(dead_code))]
+
+use dioxus::prelude::*;
+use fermi::use_atom_ref;
+use crate::secrets::{deleteSecrets, loadSecrets, saveSecrets, secretsExist};
+use crate::state::UnlockedSecrets;
+
+/// Edits the passwords used for this session and saves them to the encrypted
+/// secret file, or unlocks a previously saved file, with a master passphrase.
+pub fn SecretsEditor(cx: Scope) -> Element
+{
+	let secrets = use_atom_ref(cx, UnlockedSecrets);
+	
+	let message = use_state(cx, || String::default());
+	let passphrase = use_state(cx, || String::default());
+	
+	let password = secrets.read().password.to_owned();
+	let videoPassword = secrets.read().videoPassword.to_owned();
+	let hasFile = secretsExist();
+	let canUnlock = hasFile && secrets.read().isEmpty();
+	let noPassphrase = passphrase.is_empty();
+	let cannotSave = noPassphrase || secrets.read().isEmpty();
+	
+	return cx.render(rsx!
+	{
+		div
+		{
+			class: "secretsEditor",
+			
+			div
+			{
+				class: "inputRow",
+				label { class: "labelInputLabel", r#for: "password", "Password:" }
+				input
+				{
+					r#type: "password",
+					id: "password",
+					name: "password",
+					value: "{password}",
+					oninput: move |evt: FormEvent| secrets.write().password = evt.value.to_owned()
+				}
+			}
+			
+			div
+			{
+				class: "inputRow",
+				label { class: "labelInputLabel", r#for: "videoPassword", "Video Password:" }
+				input
+				{
+					r#type: "password",
+					id: "videoPassword",
+					name: "videoPassword",
+					title: "Password for password protected videos",
+					value: "{videoPassword}",
+					oninput: move |evt: FormEvent| secrets.write().videoPassword = evt.value.to_owned()
+				}
+			}
+			
+			div
+			{
+				class: "inputRow",
+				label { class: "labelInputLabel", r#for: "masterPassphrase", "Master Passphrase:" }
+				input
+				{
+					r#type: "password",
+					id: "masterPassphrase",
+					name: "masterPassphrase",
+					title: "Encrypts the passwords so they are never saved in plain text",
+					value: "{passphrase}",
+					oninput: move |evt: FormEvent| passphrase.set(evt.value.to_owned())
+				}
+				
+				canUnlock.then(|| rsx!
+				{
+					button
+					{
+						disabled: "{noPassphrase}",
+						onclick: move |_| {
+							match loadSecrets(passphrase.get().to_owned())
+							{
+								Ok(loaded) => {
+									*secrets.write() = loaded;
+									message.set("Unlocked".to_string());
+								},
+								Err(e) => message.set(e),
+							}
+							passphrase.set(String::default());
+						},
+						"Unlock"
+					}
+				})
+				
+				button
+				{
+					disabled: "{cannotSave}",
+					onclick: move |_| {
+						match saveSecrets(&secrets.read(), passphrase.get().to_owned())
+						{
+							Ok(_) => message.set("Saved".to_string()),
+							Err(e) => message.set(e),
+						}
+						passphrase.set(String::default());
+					},
+					"Save Encrypted"
+				}
+				
+				hasFile.then(|| rsx!
+				{
+					button
+					{
+						title: "Delete the encrypted secret file and clear the passwords for this session",
+						onclick: move |_| {
+							match deleteSecrets()
+							{
+								Ok(_) => {
+									*secrets.write() = Default::default();
+									message.set("Forgotten".to_string());
+								},
+								Err(e) => message.set(e),
+							}
+							passphrase.set(String::default());
+						},
+						"Forget"
+					}
+				})
+			}
+			
+			(!message.is_empty()).then(|| rsx!
+			{
+				h6 { class: "secretsMessage", "{message}" }
+			})
+		}
+	});
+}
+
+// --------------------------------------------------
+
+/// Asks for the password needed to start a download when none has been
+/// unlocked or saved, along with an optional two-factor code. Neither is
+/// kept once the download has started. Submits the password and the code.
+#[inline_props]
+pub fn CredentialsPrompt<'a>(cx: Scope, username: String, onSubmit: EventHandler<'a, (String, String)>) -> Element<'a>
+{
+	let password = use_state(cx, || String::default());
+	let twoFactor = use_state(cx, || String::default());
+	
+	let noPassword = password.is_empty();
+	
+	return cx.render(rsx!
+	{
+		div
+		{
+			class: "credentialsPrompt",
+			
+			h5 { "Sign in as {username}" }
+			
+			div
+			{
+				class: "inputRow",
+				label { class: "labelInputLabel", "Password:" }
+				input
+				{
+					r#type: "password",
+					value: "{password}",
+					oninput: move |evt: FormEvent| password.set(evt.value.to_owned())
+				}
+			}
+			
+			div
+			{
+				class: "inputRow",
+				label { class: "labelInputLabel", "Two-Factor Code:" }
+				input
+				{
+					r#type: "text",
+					placeholder: "Optional",
+					value: "{twoFactor}",
+					oninput: move |evt: FormEvent| twoFactor.set(evt.value.to_owned())
+				}
+			}
+			
+			button
+			{
+				disabled: "{noPassword}",
+				onclick: move |_| onSubmit.call((password.get().to_owned(), twoFactor.get().to_owned())),
+				"Start"
+			}
+		}
+	});
+}
@@ -0,0 +1,149 @@
+#![allow(non_snake_case, non_upper_case_globals)]
+#![cfg_attr(debug_assertions, allow(dead_code))]
+
+use dioxus::prelude::*;
+use crate::download::{formatTimestamp, DownloadSection};
+
+#[inline_props]
+pub fn SectionsEditor<'a>(cx: Scope,
+	sections: Vec<DownloadSection>, #[props(!optional)] duration: Option<u64>, onApply: EventHandler<'a, Vec<DownloadSection>>,
+) -> Element<'a>
+{
+	let ranges = use_ref(cx, || sections.iter()
+		.filter_map(|section| match section
+		{
+			DownloadSection::TimeRange(start, end) => Some((formatTimestamp(*start), end.map_or(String::default(), formatTimestamp))),
+			_ => None,
+		})
+		.collect::<Vec<(String, String)>>());
+	
+	let chapters = use_ref(cx, || sections.iter()
+		.filter_map(|section| match section
+		{
+			DownloadSection::Chapter(pattern) => Some(pattern.to_owned()),
+			_ => None,
+		})
+		.collect::<Vec<String>>());
+	
+	let error = use_state(cx, || String::default());
+	
+	let durationText = match duration
+	{
+		Some(d) => format!("Video Duration: {}", formatTimestamp(*d)),
+		None => "Video Duration: Unknown".to_string(),
+	};
+	
+	return cx.render(rsx!
+	{
+		div
+		{
+			class: "sectionsEditor",
+			
+			h5 { "{durationText}" }
+			
+			for (i, (start, end)) in ranges.read().iter().enumerate()
+			{
+				div
+				{
+					key: "range{i}",
+					class: "inputRow sectionRow",
+					
+					input
+					{
+						r#type: "text",
+						placeholder: "Start (00:00)",
+						value: "{start}",
+						oninput: move |evt: FormEvent| ranges.write()[i].0 = evt.value.to_owned()
+					}
+					
+					input
+					{
+						r#type: "text",
+						placeholder: "End (inf)",
+						value: "{end}",
+						oninput: move |evt: FormEvent| ranges.write()[i].1 = evt.value.to_owned()
+					}
+					
+					button
+					{
+						onclick: move |_| { ranges.write().remove(i); },
+						"Remove"
+					}
+				}
+			}
+			
+			for (i, pattern) in chapters.read().iter().enumerate()
+			{
+				div
+				{
+					key: "chapter{i}",
+					class: "inputRow sectionRow",
+					
+					input
+					{
+						r#type: "text",
+						placeholder: "Chapter title regular expression",
+						value: "{pattern}",
+						oninput: move |evt: FormEvent| chapters.write()[i] = evt.value.to_owned()
+					}
+					
+					button
+					{
+						onclick: move |_| { chapters.write().remove(i); },
+						"Remove"
+					}
+				}
+			}
+			
+			(!error.is_empty()).then(|| rsx!
+			{
+				h6 { class: "error", "{error}" }
+			})
+			
+			div
+			{
+				class: "row",
+				
+				button
+				{
+					onclick: move |_| ranges.write().push((String::default(), String::default())),
+					"Add Time Range"
+				}
+				
+				button
+				{
+					onclick: move |_| chapters.write().push(String::default()),
+					"Add Chapter"
+				}
+				
+				button
+				{
+					onclick: move |_| {
+						let mut parsed = vec![];
+						let mut errors = vec![];
+						
+						ranges.read()
+							.iter()
+							.map(|(start, end)| DownloadSection::fromTimestamps(start.to_owned(), end.to_owned()))
+							.chain(chapters.read().iter().map(|pattern| DownloadSection::fromChapter(pattern.to_owned())))
+							.for_each(|result| match result.and_then(|section| section.validate(*duration).map(|_| section))
+							{
+								Ok(section) => parsed.push(section),
+								Err(e) => errors.push(e),
+							});
+						
+						match errors.is_empty()
+						{
+							true => {
+								error.set(String::default());
+								onApply.call(parsed);
+							},
+							false => error.set(errors.join("\n")),
+						}
+					},
+					"Apply"
+				}
+			}
+		}
+	});
+}
@@ -0,0 +1,372 @@
+#![allow(non_snake_case, non_upper_case_globals)]
+#![cfg_attr(debug_assertions, allow(dead_code))]
+
+use chrono::{Local, TimeZone};
+use dioxus::prelude::*;
+use fermi::{use_atom_ref, use_read, use_set, UseAtomRef};
+use log::{debug, warn};
+use std::collections::BTreeMap;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use crate::components::filter::MatchFilterEditor;
+use crate::components::input::{LabelInputRow, ToggleRow};
+use crate::download::{checkSubscription, MatchFilter, Subscription};
+use crate::state::{enqueueDownload, saveOptions, saveSubscriptions, Binary, DownloadEntry,
+	DownloaderOptions, ShowSubscriptions, Subscriptions, UrlList};
+
+const Format_LastChecked: &str = "%Y-%m-%d %H:%M";
+/// How often the subscriptions are looked at to see whether any are due.
+const SubscriptionTick: Duration = Duration::from_secs(60);
+
+/// Check every enabled subscription once its interval has passed, for as long
+/// as the app is running.
+pub fn useSubscriptionChecks(cx: Scope)
+{
+	let binary = use_read(cx, Binary);
+	let downloaderOptions = use_atom_ref(cx, DownloaderOptions);
+	let subscriptions = use_atom_ref(cx, Subscriptions);
+	let urlList = use_atom_ref(cx, UrlList);
+	
+	//Restarted whenever the binary changes so that the checks use the new one
+	use_future(cx, (binary.to_owned(),), |(binary,)|
+	{
+		to_owned![downloaderOptions, subscriptions, urlList];
+		async move
+		{
+			loop
+			{
+				let interval = downloaderOptions.read().subscriptionInterval.saturating_mul(60);
+				let due = subscriptions.read()
+					.iter()
+					.filter(|s| s.isDue(unixTimestamp(), interval))
+					.map(|s| s.url.to_owned())
+					.collect::<Vec<String>>();
+				
+				for url in due
+				{
+					refreshSubscription(binary.to_owned(), url, subscriptions.to_owned(), urlList.to_owned()).await;
+				}
+				
+				tokio::time::sleep(SubscriptionTick).await;
+			}
+		}
+	});
+}
+
+/// Check one subscription, queue the items it has not downloaded yet and
+/// record the outcome.
+async fn refreshSubscription(binary: String, url: String, subscriptions: UseAtomRef<Vec<Subscription>>, urlList: UseAtomRef<BTreeMap<usize, DownloadEntry>>)
+{
+	//The subscription may have been removed while another was being checked
+	let subscription = match subscriptions.read().iter().find(|s| s.url == url)
+	{
+		Some(s) => s.to_owned(),
+		None => return,
+	};
+	
+	let result = checkSubscription(binary, subscription.to_owned()).await;
+	
+	let queued = match &result
+	{
+		Ok((_, entries)) => {
+			let mut urls = urlList.write();
+			entries.iter()
+				.filter(|entry| enqueueDownload(&mut urls, DownloadEntry
+				{
+					url: entry.url.to_owned(),
+					expandPlaylist: false,
+					profile: Some(subscription.profile.to_owned()),
+				}))
+				.count()
+		},
+		Err(_) => 0,
+	};
+	
+	let mut list = subscriptions.write();
+	if let Some(s) = list.iter_mut().find(|s| s.url == url)
+	{
+		s.lastChecked = unixTimestamp();
+		match result
+		{
+			Ok((title, _)) => {
+				debug!("Subscription checked: {} ({} new)", url, queued);
+				if let Some(t) = title.filter(|t| !t.is_empty())
+				{
+					s.title = t;
+				}
+				s.lastError = String::default();
+				s.lastNew = queued;
+				s.totalNew += queued;
+			},
+			Err(e) => {
+				warn!("{}", e);
+				s.lastError = e;
+				s.lastNew = 0;
+			},
+		}
+	}
+	saveSubscriptions(&list);
+}
+
+fn unixTimestamp() -> u64
+{
+	return SystemTime::now()
+		.duration_since(UNIX_EPOCH)
+		.map_or(0, |d| d.as_secs());
+}
+
+fn formatLastChecked(timestamp: u64) -> String
+{
+	return match timestamp
+	{
+		0 => "Never".to_string(),
+		t => Local.timestamp_opt(t as i64, 0)
+			.single()
+			.map_or(String::default(), |time| time.format(Format_LastChecked).to_string()),
+	};
+}
+
+// --------------------------------------------------
+
+/// Lists the channels and playlists being followed, when each was last
+/// checked and how many new items were queued.
+pub fn SubscriptionsView(cx: Scope) -> Element
+{
+	let binary = use_read(cx, Binary);
+	let downloaderOptions = use_atom_ref(cx, DownloaderOptions);
+	let setShowSubscriptions = use_set(cx, ShowSubscriptions);
+	let subscriptions = use_atom_ref(cx, Subscriptions);
+	let urlList = use_atom_ref(cx, UrlList);
+	
+	let editing = use_state(cx, || None::<usize>);
+	let newUrl = use_state(cx, || String::default());
+	
+	let rows = subscriptions.read()
+		.iter()
+		.map(|s| (
+			s.displayTitle(),
+			s.url.to_owned(),
+			s.enabled,
+			formatLastChecked(s.lastChecked),
+			format!("{} new, {} in total", s.lastNew, s.totalNew),
+			s.lastError.to_owned(),
+		))
+		.collect::<Vec<(String, String, bool, String, String, String)>>();
+	
+	let editedProfile = editing.get()
+		.and_then(|i| subscriptions.read().get(i).map(|s| (i, s.profile.to_owned())));
+	
+	let updateSubscription = move |i: usize, f: &dyn Fn(&mut Subscription)|
+	{
+		let mut list = subscriptions.write();
+		if let Some(s) = list.get_mut(i)
+		{
+			f(s);
+		}
+		saveSubscriptions(&list);
+	};
+	
+	return cx.render(rsx!
+	{
+		div
+		{
+			class: "optionsOverlay",
+			
+			div
+			{
+				class: "options subscriptions",
+				
+				div
+				{
+					class: "row",
+					h1 { "Subscriptions" }
+					button
+					{
+						onclick: move |_| setShowSubscriptions(false),
+						"Close"
+					}
+				}
+				
+				LabelInputRow
+				{
+					label: "Check Interval".into(),
+					name: "subscriptionInterval".into(),
+					value: downloaderOptions.read().subscriptionInterval.to_string(),
+					title: "Minutes between checks of the subscriptions, 0 to only check them by hand".into(),
+					onInput: move |evt: FormEvent| {
+						if let Ok(val) = evt.value.parse::<u64>()
+						{
+							downloaderOptions.write().subscriptionInterval = val;
+							saveOptions(cx);
+						}
+					}
+				}
+				
+				div
+				{
+					class: "inputRow",
+					input
+					{
+						r#type: "text",
+						placeholder: "Channel or playlist URL",
+						value: "{newUrl}",
+						oninput: move |evt: FormEvent| newUrl.set(evt.value.to_owned())
+					}
+					
+					button
+					{
+						onclick: move |_| {
+							let url = newUrl.trim().to_string();
+							if !url.is_empty() && !subscriptions.read().iter().any(|s| s.url == url)
+							{
+								{
+									let mut list = subscriptions.write();
+									list.push(Subscription::new(url.to_owned()));
+									saveSubscriptions(&list);
+								}
+								newUrl.set(String::default());
+								
+								to_owned![binary, subscriptions, urlList];
+								cx.spawn(async move {
+									refreshSubscription(binary, url, subscriptions, urlList).await;
+								});
+							}
+						},
+						"Subscribe"
+					}
+				}
+				
+				for (i, (title, url, enabled, lastChecked, counts, error)) in rows.into_iter().enumerate()
+				{
+					div
+					{
+						key: "{url}",
+						class: "subscription",
+						
+						h4 { title: "{url}", "{title}" }
+						div
+						{
+							class: "row subscriptionStatus",
+							span { "Last checked: {lastChecked}" }
+							span { "{counts}" }
+						}
+						
+						(!error.is_empty()).then(|| rsx!
+						{
+							div { class: "inputError", title: "{error}", "{error}" }
+						})
+						
+						div
+						{
+							class: "row",
+							
+							ToggleRow
+							{
+								label: "Enabled".into(),
+								name: format!("subscriptionEnabled{}", i),
+								value: enabled,
+								onInput: move |evt: FormEvent| {
+									if let Ok(val) = evt.value.parse::<bool>()
+									{
+										updateSubscription(i, &|s| s.enabled = val);
+									}
+								}
+							}
+							
+							button
+							{
+								onclick: move |_| {
+									to_owned![binary, subscriptions, urlList, url];
+									cx.spawn(async move {
+										refreshSubscription(binary, url, subscriptions, urlList).await;
+									});
+								},
+								"Check Now"
+							}
+							
+							button
+							{
+								onclick: move |_| editing.set(match *editing.get() == Some(i)
+								{
+									true => None,
+									false => Some(i),
+								}),
+								"Profile"
+							}
+							
+							button
+							{
+								onclick: move |_| {
+									let mut list = subscriptions.write();
+									list.remove(i);
+									saveSubscriptions(&list);
+									editing.set(None);
+								},
+								"Remove"
+							}
+						}
+					}
+				}
+				
+				editedProfile.map(|(i, profile)| rsx!
+				{
+					div
+					{
+						class: "subscriptionProfile",
+						
+						h4 { "Profile" }
+						
+						LabelInputRow
+						{
+							label: "Format".into(),
+							name: "subscriptionFormat".into(),
+							value: profile.format.to_owned(),
+							placeholder: "The global Format".into(),
+							onInput: move |evt: FormEvent| {
+								let val = evt.value.trim().to_owned();
+								updateSubscription(i, &|s| s.profile.format = val.to_owned());
+							}
+						}
+						
+						LabelInputRow
+						{
+							label: "Output Path".into(),
+							name: "subscriptionOutputPath".into(),
+							value: profile.outputPath.to_owned(),
+							placeholder: "The global Output Path".into(),
+							onInput: move |evt: FormEvent| {
+								let val = evt.value.trim().to_owned();
+								updateSubscription(i, &|s| s.profile.outputPath = val.to_owned());
+							}
+						}
+						
+						LabelInputRow
+						{
+							label: "Archive File".into(),
+							name: "subscriptionArchivePath".into(),
+							value: profile.archivePath.to_owned(),
+							placeholder: "The managed archive".into(),
+							title: "Items recorded here are never queued again".into(),
+							onInput: move |evt: FormEvent| {
+								let val = evt.value.trim().to_owned();
+								updateSubscription(i, &|s| s.profile.archivePath = val.to_owned());
+							}
+						}
+						
+						div
+						{
+							class: "inputRow",
+							label { class: "labelInputLabel", "Match Filters:" }
+							MatchFilterEditor
+							{
+								filter: profile.matchFilter.to_owned(),
+								onChange: move |filter: MatchFilter| {
+									updateSubscription(i, &|s| s.profile.matchFilter = filter.to_owned());
+								}
+							}
+						}
+					}
+				})
+			}
+		}
+	});
+}
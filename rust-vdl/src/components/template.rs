@@ -0,0 +1,73 @@
+#![allow(non_snake_case, non_upper_case_globals)]
+#![cfg_attr(debug_assertions, allow(dead_code))]
+
+use dioxus::prelude::*;
+use crate::download::{OutputTemplateBuilder, OutputTemplateVariable};
+
+const DefaultTemplateClass: &str = "inputRow templateRow";
+
+/// A labeled output template input with a list of variables which can be
+/// appended to the template.
+#[inline_props]
+pub fn TemplateInputRow<'a>(cx: Scope,
+	label: String, name: String, value: String, variables: Vec<OutputTemplateVariable>,
+	onInput: EventHandler<'a, String>,
+	class: Option<String>, title: Option<String>,
+) -> Element<'a>
+{
+	let c = match class
+	{
+		None => DefaultTemplateClass.to_string(),
+		Some(c) => c.into(),
+	};
+	
+	let t = match title
+	{
+		None => String::default(),
+		Some(t) => t.into(),
+	};
+	
+	let builder = OutputTemplateBuilder::default();
+	let variableNames = variables.iter()
+		.map(|variable| builder.getVariableString(*variable))
+		.collect::<Vec<String>>();
+	
+	return cx.render(rsx!
+	{
+		div
+		{
+			class: "{c}",
+			label { class: "labelInputLabel", r#for: "{name}", "{label}:" }
+			input
+			{
+				r#type: "text",
+				id: "{name}",
+				name: "{name}",
+				title: "{t}",
+				value: "{value}",
+				oninput: move |evt: FormEvent| onInput.call(evt.value.to_owned())
+			}
+			
+			select
+			{
+				class: "templateVariables",
+				value: "",
+				onchange: move |evt: FormEvent| {
+					if let Some(variable) = evt.value.parse::<usize>().ok().and_then(|i| variables.get(i))
+					{
+						let mut template = OutputTemplateBuilder::new(value.to_owned());
+						template.push(*variable, None);
+						onInput.call(template.get());
+					}
+				},
+				
+				option { value: "", "Insert..." }
+				
+				for (i, variableName) in variableNames.iter().enumerate()
+				{
+					option { key: "{i}", value: "{i}", "{variableName}" }
+				}
+			}
+		}
+	});
+}
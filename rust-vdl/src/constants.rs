@@ -4,6 +4,8 @@
 use dioxus_desktop::tao::dpi::LogicalSize;
 
 pub const AppTitle: &str = "Rust Video Downloader";
+/// Read by the log4rs configs to place the log files in the logs directory.
+pub const EnvVar_LogsDir: &str = "RUST_VDL_LOGS";
 pub const FileMenuLabel: &str = "&File";
 pub const HelpMenuLabel: &str = "&Help";
 pub const Log4rsConfigFileName_Debug: &str = "config/log4rs-debug.yml";
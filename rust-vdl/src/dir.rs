@@ -2,46 +2,241 @@
 #![cfg_attr(debug_assertions, allow(dead_code))]
 
 use directories::{ProjectDirs, UserDirs};
-use std::fs::create_dir_all;
+use log::error;
+use std::fs::{create_dir_all, metadata};
+use std::path::PathBuf;
 
-pub fn getConfigDir(create: bool) -> Option<String>
+const AppName: &str = "rust-vdl";
+const Directory_Logs: &str = "logs";
+const FileName_Archive: &str = "archive.txt";
+const FileName_History: &str = "history.jsonl";
+const FileName_Options: &str = "options.json";
+const FileName_Secrets: &str = "secrets.json";
+const FileName_Subscriptions: &str = "subscriptions.json";
+
+/// Why an output directory cannot be downloaded into.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum OutputDirProblem
+{
+	/// The directory does not exist yet but could be created.
+	Missing,
+	NotADirectory,
+	NotWritable,
+	/// The path could not be checked at all.
+	Unreadable(String),
+}
+
+impl std::fmt::Display for OutputDirProblem
 {
-	let mut path = None;
-	if let Some(dirs) = ProjectDirs::from("", "", "rust-vdl")
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
 	{
-		let pathStr = dirs.config_dir().to_str().unwrap().to_string();
-		if create
+		let formatted = match self
 		{
-			let _ = create_dir_all(pathStr.clone());
-		}
-		path = Some(pathStr);
+			Self::Missing => "The directory does not exist".to_string(),
+			Self::NotADirectory => "The path is not a directory".to_string(),
+			Self::NotWritable => "The directory is not writable".to_string(),
+			Self::Unreadable(reason) => reason.to_owned(),
+		};
+		return f.write_str(formatted.as_str());
+	}
+}
+
+pub fn getConfigDir(create: bool) -> Option<PathBuf>
+{
+	return projectDirs().map(|dirs| ensureDir(dirs.config_dir().to_path_buf(), create));
+}
+
+pub fn getDataDir(create: bool) -> Option<PathBuf>
+{
+	return projectDirs().map(|dirs| ensureDir(dirs.data_dir().to_path_buf(), create));
+}
+
+/// Logs are local to the machine, so they are kept out of a roaming profile on Windows.
+pub fn getLogsDir(create: bool) -> Option<PathBuf>
+{
+	return projectDirs().map(|dirs| ensureDir(dirs.data_local_dir().join(Directory_Logs), create));
+}
+
+pub fn getArchivePath(create: bool) -> Option<PathBuf>
+{
+	return getConfigDir(create).map(|dir| dir.join(FileName_Archive));
+}
+
+pub fn getHistoryPath(create: bool) -> Option<PathBuf>
+{
+	return getDataDir(create).map(|dir| dir.join(FileName_History));
+}
+
+pub fn getOptionsPath(create: bool) -> Option<PathBuf>
+{
+	return getConfigDir(create).map(|dir| dir.join(FileName_Options));
+}
+
+pub fn getSecretsPath(create: bool) -> Option<PathBuf>
+{
+	return getConfigDir(create).map(|dir| dir.join(FileName_Secrets));
+}
+
+pub fn getSubscriptionsPath(create: bool) -> Option<PathBuf>
+{
+	return getConfigDir(create).map(|dir| dir.join(FileName_Subscriptions));
+}
+
+/// The options used to be saved by appending a Windows separator to the config
+/// directory, which elsewhere left a file named `rust-vdl\options.json` beside
+/// the directory instead of inside it. Move it to where it belongs, unless
+/// options have already been saved there.
+#[cfg(not(windows))]
+pub fn migrateMisplacedFiles()
+{
+	use log::{debug, warn};
+	use std::fs::rename;
+	
+	let config = match getConfigDir(false)
+	{
+		Some(dir) => dir,
+		None => return,
+	};
+	
+	let misplaced = PathBuf::from(format!("{}\\{}", config.display(), FileName_Options));
+	if !misplaced.is_file()
+	{
+		return;
 	}
 	
-	return path;
+	let options = ensureDir(config, true).join(FileName_Options);
+	if options.exists()
+	{
+		warn!("Leaving the misplaced options file {} as options are already saved in {}", misplaced.display(), options.display());
+		return;
+	}
+	
+	match rename(&misplaced, &options)
+	{
+		Ok(_) => debug!("Moved the options file from {} to {}", misplaced.display(), options.display()),
+		Err(e) => error!("Failed to move the options file from {} to {}: {}", misplaced.display(), options.display(), e),
+	}
 }
 
-pub fn getOptionsPath(create: bool) -> Option<String>
+/// The path was built correctly on Windows, so there is nothing to move.
+#[cfg(windows)]
+pub fn migrateMisplacedFiles() {}
+
+/// The history used to be kept with the config, which is meant for settings
+/// rather than a record which keeps growing. Move it to the data directory,
+/// unless history has already been recorded there.
+pub fn migrateHistory()
 {
-	return match getConfigDir(create)
+	use log::{debug, warn};
+	use std::fs::rename;
+	
+	let misplaced = match getConfigDir(false)
 	{
-		Some(path) => Some(path.clone() + "\\options.json"),
-		None => None,
+		Some(dir) => dir.join(FileName_History),
+		None => return,
 	};
+	
+	if !misplaced.is_file()
+	{
+		return;
+	}
+	
+	let history = match getHistoryPath(true)
+	{
+		Some(path) => path,
+		None => return,
+	};
+	
+	if history.exists()
+	{
+		warn!("Leaving the old history file {} as history is already recorded in {}", misplaced.display(), history.display());
+		return;
+	}
+	
+	match rename(&misplaced, &history)
+	{
+		Ok(_) => debug!("Moved the history file from {} to {}", misplaced.display(), history.display()),
+		Err(e) => error!("Failed to move the history file from {} to {}: {}", misplaced.display(), history.display(), e),
+	}
+}
+
+/// Check that the output directory exists, is a directory and can be written
+/// to. An empty path is the working directory, which yt-dlp falls back to.
+pub fn validateOutputDir(path: String) -> Result<(), OutputDirProblem>
+{
+	let trimmed = match path.trim().is_empty()
+	{
+		true => ".".to_string(),
+		false => path.trim().to_string(),
+	};
+	
+	let meta = match metadata(&trimmed)
+	{
+		Ok(m) => m,
+		Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Err(OutputDirProblem::Missing),
+		Err(e) => return Err(OutputDirProblem::Unreadable(format!("Failed to read {}: {}", trimmed, e))),
+	};
+	
+	if !meta.is_dir()
+	{
+		return Err(OutputDirProblem::NotADirectory);
+	}
+	
+	return match isWritable(trimmed.as_str(), &meta)
+	{
+		true => Ok(()),
+		false => Err(OutputDirProblem::NotWritable),
+	};
+}
+
+/// Create the output directory along with any missing parents.
+pub fn createOutputDir(path: String) -> Result<(), String>
+{
+	return create_dir_all(path.trim())
+		.map_err(|e| format!("Failed to create {}: {}", path.trim(), e));
+}
+
+/// Asks the OS rather than reading the permission bits, which say nothing of
+/// group membership, ACLs or read-only mounts.
+#[cfg(unix)]
+fn isWritable(path: &str, _meta: &std::fs::Metadata) -> bool
+{
+	return match std::ffi::CString::new(path)
+	{
+		Ok(p) => unsafe { libc::access(p.as_ptr(), libc::W_OK) == 0 },
+		Err(_) => false,
+	};
+}
+
+#[cfg(not(unix))]
+fn isWritable(_path: &str, meta: &std::fs::Metadata) -> bool
+{
+	return !meta.permissions().readonly();
 }
 
 pub fn getUserDownloadsDir() -> String
 {
-	let mut defaultOutput = String::default();
-	if let Some(dirs) = UserDirs::new()
+	return UserDirs::new()
+		.and_then(|dirs| dirs.download_dir().filter(|dl| dl.is_dir()).map(|dl| dl.to_string_lossy().to_string()))
+		.unwrap_or_default();
+}
+
+// --------------------------------------------------
+
+fn projectDirs() -> Option<ProjectDirs>
+{
+	return ProjectDirs::from("", "", AppName);
+}
+
+fn ensureDir(path: PathBuf, create: bool) -> PathBuf
+{
+	if create
 	{
-		if let Some(dl) = dirs.download_dir()
+		if let Err(e) = create_dir_all(&path)
 		{
-			if dl.exists() && dl.is_dir()
-			{
-				defaultOutput = dl.to_str().unwrap().to_string();
-			}
+			error!("Failed to create {}: {}", path.display(), e);
 		}
 	}
 	
-	return defaultOutput;
+	return path;
 }
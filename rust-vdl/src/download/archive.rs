@@ -0,0 +1,78 @@
+#![allow(non_snake_case, non_upper_case_globals)]
+#![cfg_attr(debug_assertions, allow(dead_code))]
+
+use log::{debug, error};
+use std::fs::{read_to_string, write};
+
+/// A single line of a yt-dlp download archive, identifying a video which has
+/// already been downloaded by its extractor and id, e.g. `youtube dQw4w9WgXcQ`.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ArchiveEntry
+{
+	pub extractor: String,
+	pub id: String,
+}
+
+impl ArchiveEntry
+{
+	pub fn parse(line: String) -> Option<Self>
+	{
+		let mut parts = line.trim().splitn(2, ' ');
+		return match (parts.next(), parts.next())
+		{
+			(Some(extractor), Some(id)) if !extractor.is_empty() && !id.trim().is_empty() => Some(Self
+			{
+				extractor: extractor.to_string(),
+				id: id.trim().to_string(),
+			}),
+			_ => None,
+		};
+	}
+}
+
+impl std::fmt::Display for ArchiveEntry
+{
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+	{
+		let formatted = format!("{} {}", self.extractor, self.id);
+		return f.write_str(formatted.as_str());
+	}
+}
+
+// --------------------------------------------------
+
+/// Read every entry in the archive file. A missing file is simply an empty
+/// archive; lines which are not valid entries are dropped.
+pub fn readArchive(path: String) -> Vec<ArchiveEntry>
+{
+	return match read_to_string(&path)
+	{
+		Ok(text) => text.lines()
+			.filter_map(|line| ArchiveEntry::parse(line.to_string()))
+			.collect(),
+		Err(e) => {
+			debug!("Could not read the download archive {}: {}", path, e);
+			vec![]
+		},
+	};
+}
+
+/// Replace the contents of the archive file with the given entries.
+pub fn writeArchive(path: String, entries: &Vec<ArchiveEntry>) -> bool
+{
+	let text = entries.iter()
+		.map(|entry| format!("{}\n", entry))
+		.collect::<String>();
+	
+	return match write(&path, text)
+	{
+		Ok(_) => {
+			debug!("Download archive saved: {}", path);
+			true
+		},
+		Err(e) => {
+			error!("Failed to save the download archive {}: {}", path, e);
+			false
+		},
+	};
+}
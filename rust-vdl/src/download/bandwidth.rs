@@ -0,0 +1,35 @@
+#![allow(non_snake_case, non_upper_case_globals)]
+#![cfg_attr(debug_assertions, allow(dead_code))]
+
+use std::collections::BTreeMap;
+use super::size::ByteSize;
+
+/// The least any download is given, so that a crowded queue still moves.
+const MinimumShare: u64 = 16 * 1024;
+
+/// The rate a download should be limited to, or None to leave it to the
+/// per-process `limitRate`.
+///
+/// An entry with its own rate keeps it. Otherwise it gets an equal share of
+/// the global budget, after the rates the other active entries were given by
+/// hand have been taken out of it. The entry counts as active whether or not
+/// it has been registered yet, since it is about to start.
+pub fn limitRateFor(key: usize, active: &BTreeMap<usize, Option<ByteSize>>, budget: Option<ByteSize>, rate: Option<ByteSize>) -> Option<ByteSize>
+{
+	if rate.is_some()
+	{
+		return rate;
+	}
+	
+	let budget = budget?;
+	let others = active.iter()
+		.filter(|(k, _)| **k != key)
+		.map(|(_, r)| *r)
+		.collect::<Vec<Option<ByteSize>>>();
+	
+	let reserved = others.iter().flatten().map(|r| r.bytes()).sum::<u64>();
+	let sharing = others.iter().filter(|r| r.is_none()).count() as u64 + 1;
+	
+	let share = budget.bytes().saturating_sub(reserved) / sharing;
+	return Some(ByteSize(share.max(MinimumShare)));
+}
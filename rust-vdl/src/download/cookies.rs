@@ -0,0 +1,273 @@
+#![allow(non_snake_case, non_upper_case_globals)]
+#![cfg_attr(debug_assertions, allow(dead_code))]
+
+use serde::{Deserialize, Serialize};
+use std::fs::read_to_string;
+use std::path::PathBuf;
+
+const CookieFields: usize = 7;
+const Prefix_HttpOnly: &str = "#HttpOnly_";
+
+/// Validate that a file is a Netscape cookie file, the format yt-dlp reads
+/// with `--cookies`. Returns the number of cookies found.
+pub fn validateCookieFile(path: String) -> Result<usize, String>
+{
+	let text = read_to_string(&path).map_err(|e| format!("Cannot read cookie file '{}': {}", path, e))?;
+	
+	let mut count = 0;
+	for (i, line) in text.lines().enumerate()
+	{
+		let line = line.strip_prefix(Prefix_HttpOnly).unwrap_or(line);
+		if line.trim().is_empty() || line.starts_with('#')
+		{
+			continue;
+		}
+		
+		let fields = line.split('\t').collect::<Vec<&str>>();
+		if fields.len() != CookieFields
+		{
+			return Err(format!("Line {} of the cookie file has {} fields instead of {}, is it in Netscape format?", i + 1, fields.len(), CookieFields));
+		}
+		
+		let flags = [fields[1], fields[3]];
+		if flags.iter().any(|f| *f != "TRUE" && *f != "FALSE")
+		{
+			return Err(format!("Line {} of the cookie file has an invalid TRUE/FALSE flag", i + 1));
+		}
+		
+		if !fields[4].is_empty() && fields[4].parse::<i64>().is_err()
+		{
+			return Err(format!("Line {} of the cookie file has an invalid expiry '{}'", i + 1, fields[4]));
+		}
+		
+		count += 1;
+	}
+	
+	return match count
+	{
+		0 => Err("The cookie file contains no cookies".to_string()),
+		n => Ok(n),
+	};
+}
+
+// --------------------------------------------------
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum Browser
+{
+	Brave,
+	Chrome,
+	Chromium,
+	Edge,
+	Firefox,
+	Opera,
+	Safari,
+	Vivaldi,
+}
+
+impl Browser
+{
+	pub const All: [Browser; 8] = [
+		Self::Brave,
+		Self::Chrome,
+		Self::Chromium,
+		Self::Edge,
+		Self::Firefox,
+		Self::Opera,
+		Self::Safari,
+		Self::Vivaldi,
+	];
+	
+	pub fn key(&self) -> &'static str
+	{
+		return match self
+		{
+			Self::Brave => "brave",
+			Self::Chrome => "chrome",
+			Self::Chromium => "chromium",
+			Self::Edge => "edge",
+			Self::Firefox => "firefox",
+			Self::Opera => "opera",
+			Self::Safari => "safari",
+			Self::Vivaldi => "vivaldi",
+		};
+	}
+	
+	/// Only Firefox has container tabs.
+	pub fn hasContainers(&self) -> bool
+	{
+		return *self == Self::Firefox;
+	}
+	
+	/// Where the browser keeps its profiles on Linux, relative to the home
+	/// directory, including the Snap and Flatpak installs.
+	fn profileRoots(&self) -> Vec<&'static str>
+	{
+		return match self
+		{
+			Self::Brave => vec![".config/BraveSoftware/Brave-Browser", ".var/app/com.brave.Browser/config/BraveSoftware/Brave-Browser"],
+			Self::Chrome => vec![".config/google-chrome", ".var/app/com.google.Chrome/config/google-chrome"],
+			Self::Chromium => vec![".config/chromium", "snap/chromium/common/chromium", ".var/app/org.chromium.Chromium/config/chromium"],
+			Self::Edge => vec![".config/microsoft-edge", ".var/app/com.microsoft.Edge/config/microsoft-edge"],
+			Self::Firefox => vec![".mozilla/firefox", "snap/firefox/common/.mozilla/firefox", ".var/app/org.mozilla.firefox/.mozilla/firefox"],
+			Self::Opera => vec![".config/opera"],
+			Self::Safari => vec![],
+			Self::Vivaldi => vec![".config/vivaldi"],
+		};
+	}
+}
+
+impl std::fmt::Display for Browser
+{
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+	{
+		let formatted = match self
+		{
+			Self::Brave => "Brave",
+			Self::Chrome => "Chrome",
+			Self::Chromium => "Chromium",
+			Self::Edge => "Edge",
+			Self::Firefox => "Firefox",
+			Self::Opera => "Opera",
+			Self::Safari => "Safari",
+			Self::Vivaldi => "Vivaldi",
+		};
+		return f.write_str(formatted);
+	}
+}
+
+// --------------------------------------------------
+
+/// Where a Chromium based browser on Linux keeps the key its cookies are
+/// encrypted with.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum BrowserKeyring
+{
+	BasicText,
+	GnomeKeyring,
+	KWallet,
+}
+
+impl BrowserKeyring
+{
+	pub const All: [BrowserKeyring; 3] = [
+		Self::BasicText,
+		Self::GnomeKeyring,
+		Self::KWallet,
+	];
+	
+	pub fn key(&self) -> &'static str
+	{
+		return match self
+		{
+			Self::BasicText => "basictext",
+			Self::GnomeKeyring => "gnomekeyring",
+			Self::KWallet => "kwallet",
+		};
+	}
+}
+
+// --------------------------------------------------
+
+/// The browser, and optionally profile, keyring and container, to load
+/// cookies from with `--cookies-from-browser`.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default)]
+pub struct CookiesFromBrowser
+{
+	/// Cookies are not loaded from a browser when None.
+	pub browser: Option<Browser>,
+	pub profile: String,
+	pub keyring: Option<BrowserKeyring>,
+	pub container: String,
+}
+
+impl CookiesFromBrowser
+{
+	/// The argument in the form `BROWSER[+KEYRING][:PROFILE][::CONTAINER]`.
+	pub fn toArgument(&self) -> Option<String>
+	{
+		let browser = self.browser?;
+		let mut argument = browser.key().to_string();
+		
+		if let Some(keyring) = self.keyring
+		{
+			argument.push_str(format!("+{}", keyring.key()).as_str());
+		}
+		
+		if !self.profile.is_empty()
+		{
+			argument.push_str(format!(":{}", self.profile).as_str());
+		}
+		
+		if browser.hasContainers() && !self.container.is_empty()
+		{
+			argument.push_str(format!("::{}", self.container).as_str());
+		}
+		
+		return Some(argument);
+	}
+}
+
+// --------------------------------------------------
+
+/// A browser profile found on this machine.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BrowserProfile
+{
+	pub browser: Browser,
+	pub name: String,
+	pub path: PathBuf,
+}
+
+/// Find the profiles of the browsers installed for the current user by
+/// scanning the standard Firefox and Chromium profile directories.
+#[cfg(target_os = "linux")]
+pub fn detectBrowserProfiles() -> Vec<BrowserProfile>
+{
+	let home = match directories::BaseDirs::new()
+	{
+		Some(dirs) => dirs.home_dir().to_path_buf(),
+		None => return vec![],
+	};
+	
+	let mut profiles = vec![];
+	for browser in Browser::All
+	{
+		for root in browser.profileRoots()
+		{
+			if let Ok(entries) = home.join(root).read_dir()
+			{
+				for entry in entries.filter_map(|e| e.ok()).filter(|e| e.path().is_dir())
+				{
+					let path = entry.path();
+					//Every profile directory has a cookie database, whatever it is named
+					let hasCookies = match browser
+					{
+						Browser::Firefox => path.join("cookies.sqlite").exists(),
+						_ => path.join("Cookies").exists() || path.join("Network").join("Cookies").exists(),
+					};
+					
+					if hasCookies
+					{
+						profiles.push(BrowserProfile
+						{
+							browser,
+							name: entry.file_name().to_string_lossy().to_string(),
+							path,
+						});
+					}
+				}
+			}
+		}
+	}
+	
+	profiles.sort_by(|a, b| a.name.cmp(&b.name));
+	return profiles;
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn detectBrowserProfiles() -> Vec<BrowserProfile>
+{
+	return vec![];
+}
@@ -0,0 +1,273 @@
+#![allow(non_snake_case, non_upper_case_globals)]
+#![cfg_attr(debug_assertions, allow(dead_code))]
+
+use fancy_regex::Regex;
+
+const Prefix_Error: &str = "ERROR:";
+
+const Regex_AgeRestricted: &str = r"(?i)confirm your age|age[- ]restricted|age verification|inappropriate for some users";
+const Regex_DiskFull: &str = r"(?i)no space left on device|errno 28\b|disk (?:is )?full|not enough space on the disk";
+const Regex_FfmpegMissing: &str = r"(?i)ff(?:mpeg|probe)(?: and ff(?:mpeg|probe))? (?:is )?not (?:installed|found)|--ffmpeg-location";
+const Regex_Forbidden: &str = r"HTTP Error 403";
+const Regex_GeoRestricted: &str = r"(?i)available (?:in|from) your (?:country|location)|geo[- ]?restrict|not available in your region";
+const Regex_LoginRequired: &str = r"(?i)sign in|log ?in (?:is )?required|requires? (?:authentication|login|an account)|account credentials|only available for registered users|members[- ]only|--cookies";
+const Regex_NetworkTimeout: &str = r"(?i)timed? ?out|connection (?:reset|refused|aborted)|temporary failure in name resolution|network is unreachable|getaddrinfo failed|remote end closed connection";
+const Regex_Private: &str = r"(?i)private video|video is private|this (?:video|content) is private";
+const Regex_TooManyRequests: &str = r"(?i)HTTP Error 429|too many requests|rate[- ]limit";
+const Regex_Unavailable: &str = r"(?i)video unavailable|(?<!format )(?:is|are) (?:no longer|not) available|has been (?:removed|deleted|terminated)|does not exist|HTTP Error 404|not found";
+const Regex_UnsupportedUrl: &str = r"(?i)unsupported url|is not a valid url|no suitable (?:extractor|infoextractor)";
+
+/// Why a download failed, worked out from the error yt-dlp printed.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum DownloadError
+{
+	AgeRestricted,
+	DiskFull,
+	FfmpegMissing,
+	/// HTTP 403, often an expired or IP-bound media URL.
+	Forbidden,
+	GeoRestricted,
+	/// Raised by the app itself rather than yt-dlp, e.g. an unanswered prompt.
+	Internal(String),
+	LoginRequired,
+	NetworkTimeout,
+	Private,
+	/// HTTP 429, the site is rate limiting this address.
+	TooManyRequests,
+	Unavailable,
+	/// An error which did not match any known class, with the line as printed.
+	Unknown(String),
+	UnsupportedUrl,
+}
+
+impl DownloadError
+{
+	/// The keys and names of the classes yt-dlp errors are sorted into.
+	pub const Classes: [(&'static str, &'static str); 12] = [
+		("ageRestricted", "Age Restricted"),
+		("diskFull", "Disk Full"),
+		("ffmpegMissing", "ffmpeg Missing"),
+		("forbidden", "HTTP 403"),
+		("geoRestricted", "Geo Restricted"),
+		("loginRequired", "Login Required"),
+		("networkTimeout", "Network Timeout"),
+		("private", "Private"),
+		("tooManyRequests", "HTTP 429"),
+		("unavailable", "Unavailable"),
+		("unknown", "Unknown"),
+		("unsupportedUrl", "Unsupported URL"),
+	];
+	
+	/// The key of the class this error belongs to, as listed in `Classes`.
+	pub fn key(&self) -> &'static str
+	{
+		return match self
+		{
+			Self::AgeRestricted => "ageRestricted",
+			Self::DiskFull => "diskFull",
+			Self::FfmpegMissing => "ffmpegMissing",
+			Self::Forbidden => "forbidden",
+			Self::GeoRestricted => "geoRestricted",
+			Self::Internal(_) => "internal",
+			Self::LoginRequired => "loginRequired",
+			Self::NetworkTimeout => "networkTimeout",
+			Self::Private => "private",
+			Self::TooManyRequests => "tooManyRequests",
+			Self::Unavailable => "unavailable",
+			Self::Unknown(_) => "unknown",
+			Self::UnsupportedUrl => "unsupportedUrl",
+		};
+	}
+	
+	/// Advice for the user on how to get past the error, if there is any.
+	pub fn hint(&self) -> Option<&'static str>
+	{
+		return match self
+		{
+			Self::AgeRestricted => Some("Load cookies from a signed in account or set the Age Limit in Options"),
+			Self::DiskFull => Some("Free up space or choose another Output Path"),
+			Self::FfmpegMissing => Some("Install ffmpeg or set the ffmpeg Location in Options"),
+			Self::Forbidden => Some("The media URL may have expired, retrying usually helps"),
+			Self::GeoRestricted => Some("Try Geo Bypass, a Geo Bypass Country or a Geo Verification Proxy in Options"),
+			Self::LoginRequired => Some("Sign in with a Username, Netrc or Cookies in Options"),
+			Self::NetworkTimeout => Some("Check the connection, or raise the Socket Timeout in Options"),
+			Self::Private => Some("Load cookies from an account which has been granted access"),
+			Self::TooManyRequests => Some("Wait before retrying, or set a Sleep Interval in Options"),
+			Self::UnsupportedUrl => Some("Check the URL, it may have been mistyped"),
+			Self::Internal(_) | Self::Unavailable | Self::Unknown(_) => None,
+		};
+	}
+	
+	/// Whether the hint points at settings in the Options panel.
+	pub fn hasSettings(&self) -> bool
+	{
+		return matches!(self,
+			Self::AgeRestricted
+			| Self::FfmpegMissing
+			| Self::GeoRestricted
+			| Self::LoginRequired
+			| Self::NetworkTimeout
+			| Self::TooManyRequests
+		);
+	}
+	
+	/// Whether trying again without changing anything could succeed.
+	pub fn isRetryable(&self) -> bool
+	{
+		return matches!(self, Self::Forbidden | Self::NetworkTimeout | Self::TooManyRequests);
+	}
+	
+	/// The delay in seconds before the first retry, doubled on each attempt.
+	/// Rate limits need far longer to clear than a dropped connection.
+	pub fn retryBackoff(&self) -> u64
+	{
+		return match self
+		{
+			Self::TooManyRequests => 60,
+			Self::Forbidden | Self::NetworkTimeout => 5,
+			_ => 10,
+		};
+	}
+}
+
+impl std::fmt::Display for DownloadError
+{
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+	{
+		let formatted = match self
+		{
+			Self::AgeRestricted => "The video is age restricted".to_string(),
+			Self::DiskFull => "The disk is full".to_string(),
+			Self::FfmpegMissing => "ffmpeg is required but was not found".to_string(),
+			Self::Forbidden => "Access was denied (HTTP 403)".to_string(),
+			Self::GeoRestricted => "The video is not available in your country".to_string(),
+			Self::Internal(reason) => reason.to_owned(),
+			Self::LoginRequired => "The video requires signing in".to_string(),
+			Self::NetworkTimeout => "The connection timed out".to_string(),
+			Self::Private => "The video is private".to_string(),
+			Self::TooManyRequests => "Too many requests (HTTP 429)".to_string(),
+			Self::Unavailable => "The video is unavailable".to_string(),
+			Self::Unknown(line) => line.trim_start_matches(Prefix_Error).trim().to_string(),
+			Self::UnsupportedUrl => "The URL is not supported".to_string(),
+		};
+		return f.write_str(formatted.as_str());
+	}
+}
+
+// --------------------------------------------------
+
+/// Maps the error lines yt-dlp prints on stderr to a `DownloadError`.
+pub struct ErrorClassifier
+{
+	/// Checked in order, so that the more specific classes win. A geo
+	/// restricted video is also reported as unavailable, for example.
+	classes: Vec<(Regex, DownloadError)>,
+}
+
+impl ErrorClassifier
+{
+	pub fn new() -> Self
+	{
+		let classes = [
+			(Regex_DiskFull, DownloadError::DiskFull),
+			(Regex_FfmpegMissing, DownloadError::FfmpegMissing),
+			(Regex_GeoRestricted, DownloadError::GeoRestricted),
+			(Regex_AgeRestricted, DownloadError::AgeRestricted),
+			(Regex_Private, DownloadError::Private),
+			(Regex_TooManyRequests, DownloadError::TooManyRequests),
+			(Regex_Forbidden, DownloadError::Forbidden),
+			(Regex_LoginRequired, DownloadError::LoginRequired),
+			(Regex_UnsupportedUrl, DownloadError::UnsupportedUrl),
+			(Regex_NetworkTimeout, DownloadError::NetworkTimeout),
+			(Regex_Unavailable, DownloadError::Unavailable),
+		];
+		
+		return Self
+		{
+			classes: classes.into_iter()
+				.map(|(pattern, error)| (Regex::new(pattern).expect("Failed to compile Download Error regular expression."), error))
+				.collect(),
+		};
+	}
+	
+	/// Classify a line of stderr. Returns None for lines which are not errors,
+	/// such as warnings.
+	pub fn classify(&self, line: &str) -> Option<DownloadError>
+	{
+		if !line.starts_with(Prefix_Error)
+		{
+			return None;
+		}
+		
+		let error = self.classes.iter()
+			.find(|(regex, _)| regex.is_match(line).unwrap_or(false))
+			.map_or(DownloadError::Unknown(line.to_string()), |(_, error)| error.to_owned());
+		
+		return Some(error);
+	}
+}
+
+// --------------------------------------------------
+
+#[cfg(test)]
+mod tests
+{
+	use super::*;
+	
+	/// Error lines as printed by yt-dlp, each preceded by the class it belongs to.
+	const Fixtures: &str = include_str!("fixtures/errors.txt");
+	
+	fn expected(class: &str, line: &str) -> DownloadError
+	{
+		return match class
+		{
+			"AgeRestricted" => DownloadError::AgeRestricted,
+			"DiskFull" => DownloadError::DiskFull,
+			"FfmpegMissing" => DownloadError::FfmpegMissing,
+			"Forbidden" => DownloadError::Forbidden,
+			"GeoRestricted" => DownloadError::GeoRestricted,
+			"LoginRequired" => DownloadError::LoginRequired,
+			"NetworkTimeout" => DownloadError::NetworkTimeout,
+			"Private" => DownloadError::Private,
+			"TooManyRequests" => DownloadError::TooManyRequests,
+			"Unavailable" => DownloadError::Unavailable,
+			"Unknown" => DownloadError::Unknown(line.to_string()),
+			"UnsupportedUrl" => DownloadError::UnsupportedUrl,
+			_ => panic!("Unknown class '{}' in the fixtures", class),
+		};
+	}
+	
+	#[test]
+	fn classifiesFixtures()
+	{
+		let classifier = ErrorClassifier::new();
+		let fixtures = Fixtures.lines()
+			.filter(|l| !l.is_empty() && !l.starts_with('#'))
+			.map(|l| l.split_once('\t').expect("Fixture lines are a class and a line separated by a tab"))
+			.collect::<Vec<(&str, &str)>>();
+		
+		assert!(!fixtures.is_empty());
+		for (class, line) in fixtures
+		{
+			assert_eq!(classifier.classify(line), Some(expected(class, line)), "{}", line);
+		}
+	}
+	
+	#[test]
+	fn ignoresWarnings()
+	{
+		let classifier = ErrorClassifier::new();
+		assert_eq!(classifier.classify("WARNING: [youtube] Falling back to generic n function search"), None);
+		assert_eq!(classifier.classify("[download] Got error: HTTP Error 403: Forbidden. Retrying (1/10)..."), None);
+	}
+	
+	#[test]
+	fn retriesTransientErrorsOnly()
+	{
+		assert!(DownloadError::TooManyRequests.isRetryable());
+		assert!(DownloadError::NetworkTimeout.isRetryable());
+		assert!(!DownloadError::Private.isRetryable());
+		assert!(!DownloadError::UnsupportedUrl.isRetryable());
+		assert!(DownloadError::TooManyRequests.retryBackoff() > DownloadError::NetworkTimeout.retryBackoff());
+	}
+}
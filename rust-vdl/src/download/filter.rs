@@ -0,0 +1,299 @@
+#![allow(non_snake_case, non_upper_case_globals)]
+#![cfg_attr(debug_assertions, allow(dead_code))]
+
+use fancy_regex::Regex;
+use serde::{Deserialize, Serialize};
+
+const Regex_Date: &str = r"^(?:\d{8}|(?:now|today|yesterday)(?:[+-]\d+(?:day|week|month|year)s?)?)$";
+const Separator_Conditions: &str = " & ";
+
+/// Validate a date in one of the forms yt-dlp accepts for `--date`,
+/// `--datebefore` and `--dateafter`: `YYYYMMDD` or `(now|today|yesterday)[+-]N(day|week|month|year)[s]`.
+pub fn validateDate(date: String) -> Result<(), String>
+{
+	let regex = Regex::new(Regex_Date).expect("Failed to compile Date regular expression.");
+	if !regex.is_match(date.trim()).unwrap_or(false)
+	{
+		return Err(format!("Invalid date '{}', expected YYYYMMDD or e.g. today-2weeks", date));
+	}
+	
+	//Catch impossible calendar dates like 20230231
+	if date.trim().len() == 8 && date.trim().chars().all(|c| c.is_ascii_digit())
+	{
+		let month = date.trim()[4..6].parse::<u32>().unwrap_or_default();
+		let day = date.trim()[6..8].parse::<u32>().unwrap_or_default();
+		if !(1..=12).contains(&month) || !(1..=31).contains(&day)
+		{
+			return Err(format!("Invalid date '{}'", date));
+		}
+	}
+	
+	return Ok(());
+}
+
+// --------------------------------------------------
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum MatchFieldKind
+{
+	Flag,
+	Numeric,
+	Text,
+}
+
+/// The yt-dlp info fields which can be used in a match filter.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum MatchField
+{
+	AgeLimit,
+	CommentCount,
+	Description,
+	Duration,
+	IsLive,
+	LikeCount,
+	Title,
+	Uploader,
+	ViewCount,
+	WasLive,
+}
+
+impl MatchField
+{
+	pub const All: [MatchField; 10] = [
+		Self::Duration,
+		Self::ViewCount,
+		Self::LikeCount,
+		Self::CommentCount,
+		Self::AgeLimit,
+		Self::IsLive,
+		Self::WasLive,
+		Self::Title,
+		Self::Uploader,
+		Self::Description,
+	];
+	
+	pub fn kind(&self) -> MatchFieldKind
+	{
+		return match self
+		{
+			Self::IsLive
+			| Self::WasLive
+				=> MatchFieldKind::Flag,
+			
+			Self::Description
+			| Self::Title
+			| Self::Uploader
+				=> MatchFieldKind::Text,
+			
+			_ => MatchFieldKind::Numeric,
+		};
+	}
+	
+	pub fn key(&self) -> &'static str
+	{
+		return match self
+		{
+			Self::AgeLimit => "age_limit",
+			Self::CommentCount => "comment_count",
+			Self::Description => "description",
+			Self::Duration => "duration",
+			Self::IsLive => "is_live",
+			Self::LikeCount => "like_count",
+			Self::Title => "title",
+			Self::Uploader => "uploader",
+			Self::ViewCount => "view_count",
+			Self::WasLive => "was_live",
+		};
+	}
+}
+
+// --------------------------------------------------
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum MatchComparison
+{
+	Equal,
+	GreaterOrEqual,
+	GreaterThan,
+	IsFalse,
+	IsTrue,
+	LessOrEqual,
+	LessThan,
+	Matches,
+	NotEqual,
+	NotMatches,
+}
+
+impl MatchComparison
+{
+	/// The comparisons which make sense for a field of the given kind.
+	pub fn forKind(kind: MatchFieldKind) -> Vec<MatchComparison>
+	{
+		return match kind
+		{
+			MatchFieldKind::Flag => vec![Self::IsTrue, Self::IsFalse],
+			MatchFieldKind::Numeric => vec![Self::GreaterThan, Self::GreaterOrEqual, Self::LessThan, Self::LessOrEqual, Self::Equal, Self::NotEqual],
+			MatchFieldKind::Text => vec![Self::Matches, Self::NotMatches, Self::Equal, Self::NotEqual],
+		};
+	}
+	
+	pub fn operator(&self) -> &'static str
+	{
+		return match self
+		{
+			Self::Equal => "=",
+			Self::GreaterOrEqual => ">=",
+			Self::GreaterThan => ">",
+			Self::IsFalse | Self::IsTrue => "",
+			Self::LessOrEqual => "<=",
+			Self::LessThan => "<",
+			Self::Matches => "~=",
+			Self::NotEqual => "!=",
+			Self::NotMatches => "!~=",
+		};
+	}
+}
+
+impl std::fmt::Display for MatchComparison
+{
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+	{
+		let formatted = match self
+		{
+			Self::IsFalse => "is false",
+			Self::IsTrue => "is true",
+			Self::Matches => "matches",
+			Self::NotMatches => "doesn't match",
+			_ => self.operator(),
+		};
+		return f.write_str(formatted);
+	}
+}
+
+// --------------------------------------------------
+
+/// A single typed comparison against a yt-dlp info field.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum MatchCondition
+{
+	Flag(MatchField, bool),
+	Numeric(MatchField, MatchComparison, i64),
+	Text(MatchField, MatchComparison, String),
+}
+
+impl MatchCondition
+{
+	/// Build a condition from user input, making sure the comparison and
+	/// value suit the field.
+	pub fn build(field: MatchField, comparison: MatchComparison, value: String) -> Result<Self, String>
+	{
+		if !MatchComparison::forKind(field.kind()).contains(&comparison)
+		{
+			return Err(format!("'{}' cannot be compared with '{}'", field.key(), comparison));
+		}
+		
+		return match field.kind()
+		{
+			MatchFieldKind::Flag => Ok(Self::Flag(field, comparison == MatchComparison::IsTrue)),
+			
+			MatchFieldKind::Numeric => match value.trim().parse::<i64>()
+			{
+				Ok(n) => Ok(Self::Numeric(field, comparison, n)),
+				Err(_) => Err(format!("'{}' must be compared with a whole number, not '{}'", field.key(), value)),
+			},
+			
+			MatchFieldKind::Text => {
+				if let MatchComparison::Matches | MatchComparison::NotMatches = comparison
+				{
+					if let Err(e) = Regex::new(value.as_str())
+					{
+						return Err(format!("Invalid regular expression '{}': {}", value, e));
+					}
+				}
+				Ok(Self::Text(field, comparison, value.to_owned()))
+			},
+		};
+	}
+	
+	pub fn field(&self) -> MatchField
+	{
+		return match self
+		{
+			Self::Flag(field, _)
+			| Self::Numeric(field, _, _)
+			| Self::Text(field, _, _)
+				=> *field,
+		};
+	}
+	
+	pub fn toExpression(&self) -> String
+	{
+		return match self
+		{
+			Self::Flag(field, true) => field.key().to_string(),
+			Self::Flag(field, false) => format!("!{}", field.key()),
+			Self::Numeric(field, comparison, n) => format!("{}{}{}", field.key(), comparison.operator(), n),
+			Self::Text(field, comparison, text) => format!("{}{}'{}'", field.key(), comparison.operator(), escapeText(text)),
+		};
+	}
+}
+
+/// yt-dlp splits the filters on every unescaped `&`, even inside quotes. The
+/// backslash goes first, or a trailing one would escape the closing quote.
+fn escapeText(text: &String) -> String
+{
+	return text.replace('\\', "\\\\")
+		.replace('\'', "\\'")
+		.replace('&', "\\&");
+}
+
+// --------------------------------------------------
+
+/// A set of conditions which must all hold for a video to be downloaded.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct MatchFilter
+{
+	pub conditions: Vec<MatchCondition>,
+}
+
+impl MatchFilter
+{
+	pub fn isEmpty(&self) -> bool
+	{
+		return self.conditions.is_empty();
+	}
+	
+	pub fn toExpression(&self) -> String
+	{
+		return self.conditions.iter()
+			.map(|condition| condition.toExpression())
+			.collect::<Vec<String>>()
+			.join(Separator_Conditions);
+	}
+}
+
+// --------------------------------------------------
+
+#[cfg(test)]
+mod tests
+{
+	use super::*;
+	
+	#[test]
+	fn escapesText()
+	{
+		let cases = [
+			("plain", r"title='plain'"),
+			("it's", r"title='it\'s'"),
+			("this & that", r"title='this \& that'"),
+			(r"C:\", r"title='C:\\'"),
+			(r"a\'b&c", r"title='a\\\'b\&c'"),
+		];
+		
+		for (text, expected) in cases
+		{
+			let condition = MatchCondition::Text(MatchField::Title, MatchComparison::Equal, text.to_string());
+			assert_eq!(condition.toExpression(), expected, "{}", text);
+		}
+	}
+}
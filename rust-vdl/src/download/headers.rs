@@ -0,0 +1,99 @@
+#![allow(non_snake_case, non_upper_case_globals)]
+#![cfg_attr(debug_assertions, allow(dead_code))]
+
+use serde::{Deserialize, Serialize};
+
+const Mask_Secret: &str = "***";
+const SecretHeaders: [&str; 3] = ["authorization", "cookie", "proxy-authorization"];
+/// Characters allowed in a header name besides letters and digits, per RFC 9110.
+const TokenCharacters: &str = "!#$%&'*+-.^_`|~";
+
+pub const Header_Referer: &str = "Referer";
+pub const Header_UserAgent: &str = "User-Agent";
+
+/// Common user agents, for sites which only serve media to real browsers.
+pub const UserAgentPresets: [(&str, &str); 5] = [
+	("Chrome on Windows", "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36"),
+	("Firefox on Windows", "Mozilla/5.0 (Windows NT 10.0; Win64; x64; rv:121.0) Gecko/20100101 Firefox/121.0"),
+	("Firefox on Linux", "Mozilla/5.0 (X11; Linux x86_64; rv:121.0) Gecko/20100101 Firefox/121.0"),
+	("Safari on macOS", "Mozilla/5.0 (Macintosh; Intel Mac OS X 14_2) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.2 Safari/605.1.15"),
+	("Safari on iPhone", "Mozilla/5.0 (iPhone; CPU iPhone OS 17_2 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.2 Mobile/15E148 Safari/604.1"),
+];
+
+/// A custom HTTP header sent with every request via `--add-headers`.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default)]
+pub struct HttpHeader
+{
+	pub name: String,
+	pub value: String,
+}
+
+impl HttpHeader
+{
+	pub fn new(name: &str, value: &str) -> Self
+	{
+		return Self
+		{
+			name: name.to_string(),
+			value: value.to_string(),
+		};
+	}
+	
+	/// The argument in the form `FIELD:VALUE`.
+	pub fn toArgument(&self) -> String
+	{
+		return format!("{}:{}", self.name.trim(), self.value.trim());
+	}
+	
+	pub fn validate(&self) -> Result<(), String>
+	{
+		let name = self.name.trim();
+		if name.is_empty()
+		{
+			return Err("The header name is required".to_string());
+		}
+		
+		if let Some(c) = name.chars().find(|c| !c.is_ascii_alphanumeric() && !TokenCharacters.contains(*c))
+		{
+			return Err(format!("Invalid character '{}' in header name '{}'", c, name));
+		}
+		
+		if self.value.contains(['\r', '\n'])
+		{
+			return Err(format!("The value of '{}' must be a single line", name));
+		}
+		
+		return Ok(());
+	}
+}
+
+/// Validate every header, returning one error per header with an empty
+/// string for those which are fine. Header names are case insensitive, so
+/// a name repeated in any case is reported on each later occurrence.
+pub fn validateHeaders(headers: &Vec<HttpHeader>) -> Vec<String>
+{
+	return headers.iter()
+		.enumerate()
+		.map(|(i, header)| match header.validate()
+		{
+			Err(e) => e,
+			Ok(_) => match headers[..i].iter().any(|h| h.name.trim().eq_ignore_ascii_case(header.name.trim()))
+			{
+				true => format!("Duplicate header '{}'", header.name.trim()),
+				false => String::default(),
+			},
+		})
+		.collect();
+}
+
+/// Mask the value of an `--add-headers` argument when the header carries
+/// credentials.
+pub fn maskHeader(argument: &str) -> String
+{
+	return match argument.split_once(':')
+	{
+		Some((name, _)) if SecretHeaders.contains(&name.trim().to_lowercase().as_str()) => format!("{}:{}", name, Mask_Secret),
+		_ => argument.to_string(),
+	};
+}
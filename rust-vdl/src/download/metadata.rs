@@ -0,0 +1,194 @@
+#![allow(non_snake_case, non_upper_case_globals)]
+#![cfg_attr(debug_assertions, allow(dead_code))]
+
+use std::process::Stdio;
+use log::{debug, error};
+use serde::Deserialize;
+use tokio::process::Command;
+
+#[cfg(windows)] extern crate winapi;
+
+const Option_DownloadArchive: &str = "--download-archive";
+const Option_DumpSingleJson: &str = "--dump-single-json";
+const Option_FlatPlaylist: &str = "--flat-playlist";
+const Option_MatchFilters: &str = "--match-filters";
+const Option_NoPlaylist: &str = "--no-playlist";
+const Option_YesPlaylist: &str = "--yes-playlist";
+
+// --------------------------------------------------
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct VideoChapter
+{
+	pub title: String,
+	#[serde(rename = "start_time")]
+	pub startTime: f64,
+	#[serde(rename = "end_time")]
+	pub endTime: f64,
+}
+
+// --------------------------------------------------
+
+/// The subset of yt-dlp's info JSON which rust-vdl makes use of.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct VideoMetadata
+{
+	pub id: String,
+	pub title: String,
+	pub chapters: Option<Vec<VideoChapter>>,
+	pub duration: Option<f64>,
+	pub filesize: Option<u64>,
+	#[serde(rename = "filesize_approx")]
+	pub filesizeApprox: Option<u64>,
+}
+
+impl VideoMetadata
+{
+	/// The duration of the video in milliseconds, if known.
+	pub fn durationMillis(&self) -> Option<u64>
+	{
+		return self.duration.map(|d| (d * 1000.0) as u64);
+	}
+}
+
+// --------------------------------------------------
+
+/// A single entry of a flat-expanded playlist or channel.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct PlaylistEntry
+{
+	pub id: String,
+	pub title: Option<String>,
+	pub url: String,
+	pub duration: Option<f64>,
+	/// The extractor which found the entry, as it is written in a download archive.
+	#[serde(rename = "ie_key")]
+	pub extractorKey: Option<String>,
+}
+
+impl PlaylistEntry
+{
+	/// The title of the entry, falling back to its URL when the extractor
+	/// did not provide one.
+	pub fn displayTitle(&self) -> String
+	{
+		return self.title.to_owned()
+			.filter(|t| !t.is_empty())
+			.unwrap_or(self.url.to_owned());
+	}
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct PlaylistMetadata
+{
+	pub id: String,
+	pub title: Option<String>,
+	pub entries: Vec<PlaylistEntry>,
+}
+
+// --------------------------------------------------
+
+/// Ask yt-dlp for the metadata of a single video without downloading anything.
+pub async fn fetchMetadata(binary: String, video: String) -> Option<VideoMetadata>
+{
+	let mut metadata = None;
+	
+	let output = newCommand(binary)
+		.stderr(Stdio::piped())
+		.stdout(Stdio::piped())
+		.args(vec![Option_DumpSingleJson, Option_NoPlaylist, video.as_str()])
+		.output()
+		.await;
+	
+	match output
+	{
+		Ok(o) => match serde_json::from_slice::<VideoMetadata>(&o.stdout)
+		{
+			Ok(m) => {
+				debug!("Metadata fetched for: {}", video);
+				metadata = Some(m);
+			},
+			Err(e) => error!("Error parsing metadata for: {} -> {}", video, e),
+		},
+		Err(e) => error!("Error fetching metadata for: {} -> {}", video, e),
+	}
+	
+	return metadata;
+}
+
+/// Ask yt-dlp for the entries of a playlist or channel without resolving
+/// each individual video.
+pub async fn fetchPlaylist(binary: String, playlist: String) -> Option<PlaylistMetadata>
+{
+	return fetchFlatPlaylist(binary, playlist, vec![]).await;
+}
+
+/// Like `fetchPlaylist`, leaving out the entries already in the archive file
+/// and those which do not match the filter expression.
+pub async fn fetchNewEntries(binary: String, playlist: String, archive: Option<String>, matchFilter: Option<String>) -> Option<PlaylistMetadata>
+{
+	let mut extra = vec![];
+	if let Some(path) = archive
+	{
+		extra.push(Option_DownloadArchive.to_string());
+		extra.push(path);
+	}
+	
+	if let Some(filter) = matchFilter
+	{
+		extra.push(Option_MatchFilters.to_string());
+		extra.push(filter);
+	}
+	
+	return fetchFlatPlaylist(binary, playlist, extra).await;
+}
+
+async fn fetchFlatPlaylist(binary: String, playlist: String, extra: Vec<String>) -> Option<PlaylistMetadata>
+{
+	let mut metadata = None;
+	
+	let output = newCommand(binary)
+		.stderr(Stdio::piped())
+		.stdout(Stdio::piped())
+		.args(vec![Option_FlatPlaylist, Option_DumpSingleJson, Option_YesPlaylist])
+		.args(extra)
+		.arg(playlist.as_str())
+		.output()
+		.await;
+	
+	match output
+	{
+		Ok(o) => match serde_json::from_slice::<PlaylistMetadata>(&o.stdout)
+		{
+			Ok(m) => {
+				debug!("Playlist fetched for: {} ({} entries)", playlist, m.entries.len());
+				metadata = Some(m);
+			},
+			Err(e) => error!("Error parsing playlist for: {} -> {}", playlist, e),
+		},
+		Err(e) => error!("Error fetching playlist for: {} -> {}", playlist, e),
+	}
+	
+	return metadata;
+}
+
+#[cfg(windows)]
+fn newCommand(binary: String) -> Command
+{
+	let mut command = Command::new(binary);
+	command.creation_flags(winapi::um::winbase::CREATE_NO_WINDOW)
+		.kill_on_drop(true);
+	return command;
+}
+
+#[cfg(not(windows))]
+fn newCommand(binary: String) -> Command
+{
+	let mut command = Command::new(binary);
+	command.kill_on_drop(true);
+	return command;
+}
@@ -1,9 +1,58 @@
 #![allow(non_snake_case, non_upper_case_globals)]
 #![cfg_attr(debug_assertions, allow(dead_code))]
 
-//mod template;
+mod archive;
+mod bandwidth;
+mod cookies;
+mod error;
+mod filter;
+mod geo;
+mod headers;
+mod metadata;
+mod network;
+mod paths;
+mod playlist;
+mod prompt;
+mod retry;
+mod schedule;
+mod sections;
+mod signal;
+mod size;
+mod space;
+mod sleep;
+mod status;
+mod subscription;
+mod template;
 mod video;
 
-//pub use template::{OutputTemplateBuilder, OutputTemplateVariable};
-pub use video::{DownloadProgress, DownloadReset, DownloadStopped,
-	DownloadTitle, VideoDownloader, VideoDownloaderOptions};
+pub use archive::{readArchive, writeArchive, ArchiveEntry};
+pub use bandwidth::limitRateFor;
+pub use cookies::{detectBrowserProfiles, validateCookieFile, Browser, BrowserKeyring,
+	BrowserProfile, CookiesFromBrowser};
+pub use error::DownloadError;
+pub use filter::{validateDate, MatchComparison, MatchCondition, MatchField,
+	MatchFieldKind, MatchFilter};
+pub use geo::validateCountryCode;
+pub use headers::{validateHeaders, HttpHeader, Header_Referer, Header_UserAgent,
+	UserAgentPresets};
+pub use metadata::{fetchMetadata, fetchNewEntries, fetchPlaylist, PlaylistEntry, PlaylistMetadata,
+	VideoChapter, VideoMetadata};
+pub use network::{maskArguments, maskCredentials, validateProxy, validateSourceAddress};
+pub use paths::OutputPaths;
+pub use playlist::{parsePlaylistItems, playlistItemsFromIndices, PlaylistItem};
+pub use prompt::isSecretPrompt;
+pub use retry::{parseRetrySleep, validateRetries, AutoRetry, RetrySleep, RetrySleepFunction,
+	RetrySleepType};
+pub use schedule::{formatOpening, parseTimeOfDay, Schedule, ScheduleWindow, Weekdays};
+pub use sections::{formatTimestamp, parseTimestamp, DownloadSection};
+pub use signal::{killProcessGroup, pauseProcessGroup, resumeProcessGroup, CanPause};
+pub use size::ByteSize;
+pub use space::{checkDiskSpace, freeSpace};
+pub use sleep::{parseSleepSeconds, validateSleepInterval};
+pub use status::DownloadStatus;
+pub use subscription::{checkSubscription, Subscription, SubscriptionProfile};
+pub use template::{ChapterTemplateVariables, OutputTemplateBuilder,
+	OutputTemplateVariable, OutputTemplateVariables};
+pub use video::{DownloadChapter, DownloadHandlers, DownloadProgress,
+	DownloadReset, DownloadPrompt, DownloadSkipped, DownloadStarted, DownloadStopped, DownloadTitle, DownloadWaiting, SkipReason,
+	VideoDownloader, VideoDownloaderOptions};
@@ -0,0 +1,61 @@
+#![allow(non_snake_case, non_upper_case_globals)]
+#![cfg_attr(debug_assertions, allow(dead_code))]
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+const Option_Paths: &str = "--paths";
+const Separator_PathType: char = ':';
+
+/// Where yt-dlp puts each kind of file, on top of the Output Path which is the
+/// home of the finished downloads. Relative paths are relative to the home.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default)]
+pub struct OutputPaths
+{
+	pub infojson: String,
+	pub subtitle: String,
+	/// Where files are downloaded and processed before being moved to the
+	/// home, e.g. a fast local disk when the home is a network mount.
+	pub temp: String,
+	pub thumbnail: String,
+}
+
+impl OutputPaths
+{
+	/// One `--paths TYPE:PATH` pair per path which is set, the home included.
+	pub fn toArguments(&self, home: String) -> Vec<String>
+	{
+		let paths = [
+			("home", &home),
+			("temp", &self.temp),
+			("subtitle", &self.subtitle),
+			("thumbnail", &self.thumbnail),
+			("infojson", &self.infojson),
+		];
+		
+		return paths.iter()
+			.filter(|(_, path)| !path.trim().is_empty())
+			.flat_map(|(pathType, path)| [
+				Option_Paths.to_string(),
+				format!("{}{}{}", pathType, Separator_PathType, path.trim()),
+			])
+			.collect();
+	}
+	
+	/// Whether files are moved to the home once they are finished.
+	pub fn usesTemp(&self) -> bool
+	{
+		return !self.temp.trim().is_empty();
+	}
+	
+	/// The temporary path as yt-dlp will use it, a relative one being joined
+	/// onto the home.
+	pub fn resolvedTemp(&self, home: String) -> String
+	{
+		return Path::new(home.trim())
+			.join(self.temp.trim())
+			.to_string_lossy()
+			.to_string();
+	}
+}
@@ -0,0 +1,228 @@
+#![allow(non_snake_case, non_upper_case_globals)]
+#![cfg_attr(debug_assertions, allow(dead_code))]
+
+const Separator_Items: &str = ",";
+const Separator_Range: char = '-';
+const Separator_Slice: char = ':';
+const Slice_Infinite: [&str; 2] = ["inf", "infinite"];
+
+/// A single entry of a `--playlist-items` specification, `START[-:]END[:STEP]`.
+/// Indices are 1-based and negative indices count backward from the end of the
+/// playlist.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PlaylistItem
+{
+	Index(i64),
+	Range(i64, i64),
+	Slice(Option<i64>, Option<i64>, Option<i64>),
+}
+
+impl PlaylistItem
+{
+	pub fn parse(item: &str) -> Result<Self, String>
+	{
+		let item = item.trim();
+		if item.is_empty()
+		{
+			return Err("Playlist item cannot be empty".to_string());
+		}
+		
+		//Split the start off after any sign, so that a leading minus sign is not mistaken for a range
+		let split = item.char_indices()
+			.skip_while(|(i, c)| *i == 0 && (*c == '+' || *c == '-'))
+			.find(|(_, c)| !c.is_ascii_digit())
+			.map_or(item.len(), |(i, _)| i);
+		let split = match item[..split].chars().any(|c| c.is_ascii_digit())
+		{
+			true => split,
+			false => 0,
+		};
+		
+		let (start, rest) = item.split_at(split);
+		let start = parseOptionalIndex(start)?;
+		
+		let mut chars = rest.chars();
+		let separator = match chars.next()
+		{
+			None => return start.map(Self::Index).ok_or(format!("Invalid playlist item '{}'", item)),
+			Some(c) if c == Separator_Range || c == Separator_Slice => c,
+			Some(_) => return Err(format!("Invalid playlist item '{}'", item)),
+		};
+		
+		let (end, step) = match chars.as_str().split_once(Separator_Slice)
+		{
+			Some((end, step)) => (end, Some(step)),
+			None => (chars.as_str(), None),
+		};
+		
+		let end = match Slice_Infinite.contains(&end.trim())
+		{
+			true => None,
+			false => parseOptionalIndex(end)?,
+		};
+		
+		let step = match step.map(|s| s.trim()).filter(|s| !s.is_empty())
+		{
+			Some(s) => match s.parse::<i64>()
+			{
+				Ok(0) => return Err(format!("Playlist slice step cannot be zero in '{}'", item)),
+				Ok(n) => Some(n),
+				Err(_) => return Err(format!("Invalid playlist slice step '{}'", s)),
+			},
+			None => None,
+		};
+		
+		//yt-dlp treats both separators the same, a plain range is kept as one so that it reads as written
+		return Ok(match (separator, start, end, step)
+		{
+			(Separator_Range, Some(s), Some(e), None) => Self::Range(s, e),
+			_ => Self::Slice(start, end, step),
+		});
+	}
+	
+	/// Whether the 1-based `index` is selected by this item, in a playlist of `count` entries.
+	pub fn contains(&self, index: usize, count: usize) -> bool
+	{
+		let index = index as i64;
+		let resolve = |i: i64| match i < 0
+		{
+			true => count as i64 + i + 1,
+			false => i,
+		};
+		
+		return match *self
+		{
+			Self::Index(i) => resolve(i) == index,
+			Self::Range(start, end) => {
+				let (s, e) = (resolve(start), resolve(end));
+				s.min(e) <= index && index <= s.max(e)
+			},
+			Self::Slice(start, end, step) => {
+				let step = step.unwrap_or(1);
+				let s = start.map_or(match step > 0 { true => 1, false => count as i64 }, resolve);
+				let e = end.map_or(match step > 0 { true => count as i64, false => 1 }, resolve);
+				match step > 0
+				{
+					true => s <= index && index <= e && (index - s) % step == 0,
+					false => e <= index && index <= s && (s - index) % -step == 0,
+				}
+			},
+		};
+	}
+}
+
+impl std::fmt::Display for PlaylistItem
+{
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+	{
+		let optional = |i: Option<i64>| i.map_or(String::default(), |i| i.to_string());
+		let formatted = match *self
+		{
+			Self::Index(i) => i.to_string(),
+			Self::Range(start, end) => format!("{}{}{}", start, Separator_Range, end),
+			Self::Slice(start, end, None) => format!("{}{}{}", optional(start), Separator_Slice, optional(end)),
+			Self::Slice(start, end, step) => format!("{}{}{}{}{}", optional(start), Separator_Slice, optional(end), Separator_Slice, optional(step)),
+		};
+		return f.write_str(formatted.as_str());
+	}
+}
+
+// --------------------------------------------------
+
+/// Parse and validate a full `--playlist-items` specification, e.g. `1-5,10,-3:`.
+pub fn parsePlaylistItems(spec: String) -> Result<Vec<PlaylistItem>, String>
+{
+	return spec.split(Separator_Items)
+		.map(PlaylistItem::parse)
+		.collect();
+}
+
+/// Generate a compact `--playlist-items` specification from a list of 1-based indices.
+pub fn playlistItemsFromIndices(indices: Vec<usize>) -> String
+{
+	let mut sorted = indices.to_owned();
+	sorted.sort_unstable();
+	sorted.dedup();
+	
+	let mut items = vec![];
+	let mut iter = sorted.into_iter().peekable();
+	while let Some(start) = iter.next()
+	{
+		let mut end = start;
+		while iter.peek() == Some(&(end + 1))
+		{
+			end = iter.next().unwrap_or(end);
+		}
+		
+		items.push(match start == end
+		{
+			true => PlaylistItem::Index(start as i64),
+			false => PlaylistItem::Range(start as i64, end as i64),
+		});
+	}
+	
+	return items.iter()
+		.map(|item| item.to_string())
+		.collect::<Vec<String>>()
+		.join(Separator_Items);
+}
+
+fn parseIndex(index: &str) -> Result<i64, String>
+{
+	return match index.trim().parse::<i64>()
+	{
+		Ok(0) => Err("Playlist indices start at 1".to_string()),
+		Ok(i) => Ok(i),
+		Err(_) => Err(format!("Invalid playlist index '{}'", index)),
+	};
+}
+
+fn parseOptionalIndex(index: &str) -> Result<Option<i64>, String>
+{
+	return match index.trim().is_empty()
+	{
+		true => Ok(None),
+		false => Ok(Some(parseIndex(index)?)),
+	};
+}
+
+// --------------------------------------------------
+
+#[cfg(test)]
+mod tests
+{
+	use super::*;
+	
+	#[test]
+	fn parsesItems()
+	{
+		let cases = [
+			("3", PlaylistItem::Index(3)),
+			("-3", PlaylistItem::Index(-3)),
+			("1-5", PlaylistItem::Range(1, 5)),
+			("-5--1", PlaylistItem::Range(-5, -1)),
+			("1:5", PlaylistItem::Slice(Some(1), Some(5), None)),
+			("1-10:2", PlaylistItem::Slice(Some(1), Some(10), Some(2))),
+			("1:10:2", PlaylistItem::Slice(Some(1), Some(10), Some(2))),
+			("5-inf", PlaylistItem::Slice(Some(5), None, None)),
+			("5:infinite", PlaylistItem::Slice(Some(5), None, None)),
+			("-3:", PlaylistItem::Slice(Some(-3), None, None)),
+			("::-1", PlaylistItem::Slice(None, None, Some(-1))),
+			("-", PlaylistItem::Slice(None, None, None)),
+		];
+		
+		for (spec, expected) in cases
+		{
+			assert_eq!(PlaylistItem::parse(spec), Ok(expected), "{}", spec);
+		}
+	}
+	
+	#[test]
+	fn rejectsInvalidItems()
+	{
+		for spec in ["", "0", "1x", "1-5:0", "a-b", "1:2:3:4"]
+		{
+			assert!(PlaylistItem::parse(spec).is_err(), "{}", spec);
+		}
+	}
+}
@@ -0,0 +1,81 @@
+#![allow(non_snake_case, non_upper_case_globals)]
+#![cfg_attr(debug_assertions, allow(dead_code))]
+
+use bytes::BytesMut;
+use fancy_regex::Regex;
+use tokio_util::codec::{Decoder, LinesCodec, LinesCodecError};
+
+/// yt-dlp asks for passwords and codes as "Type ... and press [Return]: ", and
+/// ffmpeg asks before overwriting. Anchored to the start of the pending text,
+/// so a chunk of ordinary output which happens to end in "password:" is not
+/// mistaken for one.
+const Regex_Prompt: &str = r"^(?:Type .+ and press \[Return\]:|File '.+' already exists\. Overwrite\? \[y/N\])\s*$";
+const Regex_SecretPrompt: &str = r"(?i)password|two-factor|2fa|code";
+
+/// Whether the answer to a prompt should be hidden while it is typed.
+pub fn isSecretPrompt(text: String) -> bool
+{
+	let regex = Regex::new(Regex_SecretPrompt).expect("Failed to compile Secret Prompt regular expression.");
+	return regex.is_match(text.as_str()).unwrap_or(false);
+}
+
+/// A line of output from yt-dlp, or a prompt waiting for an answer on stdin.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum OutputLine
+{
+	Line(String),
+	Prompt(String),
+}
+
+// --------------------------------------------------
+
+/// Splits output into lines like `LinesCodec`, but also yields the pending
+/// text when it looks like a prompt. Prompts are not followed by a newline,
+/// so they would otherwise never be seen while the child waits for an answer.
+pub struct PromptLinesCodec
+{
+	inner: LinesCodec,
+	prompt: Regex,
+}
+
+impl PromptLinesCodec
+{
+	pub fn new() -> Self
+	{
+		return Self
+		{
+			inner: LinesCodec::new(),
+			prompt: Regex::new(Regex_Prompt).expect("Failed to compile Prompt regular expression."),
+		};
+	}
+}
+
+impl Decoder for PromptLinesCodec
+{
+	type Item = OutputLine;
+	type Error = LinesCodecError;
+	
+	fn decode(&mut self, buf: &mut BytesMut) -> Result<Option<OutputLine>, LinesCodecError>
+	{
+		if let Some(line) = self.inner.decode(buf)?
+		{
+			return Ok(Some(OutputLine::Line(line)));
+		}
+		
+		let pending = String::from_utf8_lossy(buf).to_string();
+		if !pending.is_empty() && self.prompt.is_match(pending.as_str()).unwrap_or(false)
+		{
+			buf.clear();
+			//The inner codec remembers how far it has searched, which no longer applies
+			self.inner = LinesCodec::new();
+			return Ok(Some(OutputLine::Prompt(pending.trim_end().to_string())));
+		}
+		
+		return Ok(None);
+	}
+	
+	fn decode_eof(&mut self, buf: &mut BytesMut) -> Result<Option<OutputLine>, LinesCodecError>
+	{
+		return Ok(self.inner.decode_eof(buf)?.map(OutputLine::Line));
+	}
+}
@@ -0,0 +1,254 @@
+#![allow(non_snake_case, non_upper_case_globals)]
+#![cfg_attr(debug_assertions, allow(dead_code))]
+
+use serde::{Deserialize, Serialize};
+use super::error::DownloadError;
+
+const Default_AutoRetryAttempts: usize = 3;
+const Infinite: &str = "infinite";
+/// An hour, the longest the backoff is allowed to grow to.
+const MaxAutoRetryDelay: u64 = 3600;
+const Separator_Type: char = ':';
+const Separator_Values: char = ':';
+
+/// Validate a retry count as yt-dlp accepts it: a whole number or "infinite".
+pub fn validateRetries(retries: String) -> Result<(), String>
+{
+	let trimmed = retries.trim();
+	if trimmed.eq_ignore_ascii_case(Infinite) || trimmed.parse::<u64>().is_ok()
+	{
+		return Ok(());
+	}
+	
+	return Err(format!("Invalid retry count '{}', expected a whole number or '{}'", retries, Infinite));
+}
+
+/// Parse a list of `--retry-sleep` expressions separated by whitespace or commas.
+pub fn parseRetrySleep(spec: String) -> Result<Vec<RetrySleep>, String>
+{
+	return spec.split(|c: char| c == ',' || c.is_whitespace())
+		.filter(|part| !part.is_empty())
+		.map(|part| RetrySleep::parse(part.to_string()))
+		.collect();
+}
+
+// --------------------------------------------------
+
+/// The kinds of retry which `--retry-sleep` can be set for.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum RetrySleepType
+{
+	Extractor,
+	FileAccess,
+	Fragment,
+	Http,
+}
+
+impl RetrySleepType
+{
+	pub const All: [RetrySleepType; 4] = [
+		Self::Http,
+		Self::Fragment,
+		Self::FileAccess,
+		Self::Extractor,
+	];
+	
+	pub fn key(&self) -> &'static str
+	{
+		return match self
+		{
+			Self::Extractor => "extractor",
+			Self::FileAccess => "file_access",
+			Self::Fragment => "fragment",
+			Self::Http => "http",
+		};
+	}
+}
+
+// --------------------------------------------------
+
+/// How long to sleep between retries, in seconds, as a function of the
+/// number of the retry.
+#[derive(Clone, Debug, PartialEq)]
+pub enum RetrySleepFunction
+{
+	Constant(f64),
+	/// `linear=START[:END[:STEP]]`, STEP defaulting to 1.
+	Linear(f64, Option<f64>, Option<f64>),
+	/// `exp=START[:END[:BASE]]`, BASE defaulting to 2.
+	Exponential(f64, Option<f64>, Option<f64>),
+}
+
+impl std::fmt::Display for RetrySleepFunction
+{
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+	{
+		let (name, start, end, step) = match self
+		{
+			Self::Constant(seconds) => return f.write_str(seconds.to_string().as_str()),
+			Self::Linear(start, end, step) => ("linear", start, end, step),
+			Self::Exponential(start, end, base) => ("exp", start, end, base),
+		};
+		
+		let mut formatted = format!("{}={}", name, start);
+		match (end, step)
+		{
+			(Some(e), Some(s)) => formatted.push_str(format!(":{}:{}", e, s).as_str()),
+			(None, Some(s)) => formatted.push_str(format!("::{}", s).as_str()),
+			(Some(e), None) => formatted.push_str(format!(":{}", e).as_str()),
+			(None, None) => {},
+		}
+		
+		return f.write_str(formatted.as_str());
+	}
+}
+
+// --------------------------------------------------
+
+/// A single `--retry-sleep` expression, e.g. `fragment:exp=1:20`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RetrySleep
+{
+	/// The kind of retry this applies to, or all of them if None.
+	pub retryType: Option<RetrySleepType>,
+	pub function: RetrySleepFunction,
+}
+
+impl RetrySleep
+{
+	pub fn parse(text: String) -> Result<Self, String>
+	{
+		let trimmed = text.trim();
+		
+		let (retryType, expression) = match trimmed.split_once(Separator_Type)
+		{
+			Some((prefix, rest)) if !prefix.contains('=') => match RetrySleepType::All.iter().find(|t| t.key() == prefix)
+			{
+				Some(t) => (Some(*t), rest),
+				None => return Err(format!("Unknown retry type '{}', expected one of http, fragment, file_access or extractor", prefix)),
+			},
+			_ => (None, trimmed),
+		};
+		
+		let function = match expression.split_once('=')
+		{
+			None => RetrySleepFunction::Constant(parseSeconds(expression, &text)?),
+			Some((name, values)) => {
+				let mut parts = values.split(Separator_Values);
+				let start = parseSeconds(parts.next().unwrap_or_default(), &text)?;
+				let end = parseOptionalSeconds(parts.next(), &text)?;
+				let step = parseOptionalSeconds(parts.next(), &text)?;
+				if parts.next().is_some()
+				{
+					return Err(format!("Too many values in retry sleep '{}'", text));
+				}
+				
+				if let Some(e) = end.filter(|e| *e < start)
+				{
+					return Err(format!("The end {} is less than the start {} in '{}'", e, start, text));
+				}
+				
+				match name
+				{
+					"linear" => RetrySleepFunction::Linear(start, end, step),
+					"exp" => RetrySleepFunction::Exponential(start, end, step),
+					_ => return Err(format!("Unknown retry sleep function '{}', expected linear or exp", name)),
+				}
+			},
+		};
+		
+		return Ok(Self { retryType, function });
+	}
+}
+
+impl std::fmt::Display for RetrySleep
+{
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+	{
+		let formatted = match self.retryType
+		{
+			Some(t) => format!("{}{}{}", t.key(), Separator_Type, self.function),
+			None => self.function.to_string(),
+		};
+		return f.write_str(formatted.as_str());
+	}
+}
+
+// --------------------------------------------------
+
+fn parseSeconds(value: &str, text: &String) -> Result<f64, String>
+{
+	return value.trim()
+		.parse::<f64>()
+		.ok()
+		.filter(|v| v.is_finite() && *v >= 0.0)
+		.ok_or(format!("Invalid number of seconds '{}' in retry sleep '{}'", value, text));
+}
+
+fn parseOptionalSeconds(value: Option<&str>, text: &String) -> Result<Option<f64>, String>
+{
+	return match value.filter(|v| !v.is_empty())
+	{
+		Some(v) => parseSeconds(v, text).map(Some),
+		None => Ok(None),
+	};
+}
+
+// --------------------------------------------------
+
+/// The app's own retry policy, for when yt-dlp has given up on a download.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default)]
+pub struct AutoRetry
+{
+	/// Retries after the first attempt, 0 to never retry automatically.
+	pub maxAttempts: usize,
+	/// Seconds before the first retry, doubled on each retry after it. 0 uses
+	/// a delay suited to the error.
+	pub backoff: u64,
+	/// Keys of the error classes which count as transient.
+	pub transient: Vec<String>,
+}
+
+impl Default for AutoRetry
+{
+	fn default() -> Self
+	{
+		return Self
+		{
+			maxAttempts: Default_AutoRetryAttempts,
+			backoff: 0,
+			transient: [DownloadError::Forbidden, DownloadError::NetworkTimeout, DownloadError::TooManyRequests]
+				.iter()
+				.map(|e| e.key().to_string())
+				.collect(),
+		};
+	}
+}
+
+impl AutoRetry
+{
+	pub fn isTransient(&self, error: &DownloadError) -> bool
+	{
+		return self.transient.iter().any(|key| key == error.key());
+	}
+	
+	/// The seconds to wait before making the given retry, counting from 1, or
+	/// None when the error is not transient or the retries have run out.
+	pub fn delay(&self, error: &DownloadError, attempt: usize) -> Option<u64>
+	{
+		if attempt == 0 || attempt > self.maxAttempts || !self.isTransient(error)
+		{
+			return None;
+		}
+		
+		let base = match self.backoff
+		{
+			0 => error.retryBackoff(),
+			seconds => seconds,
+		};
+		
+		let factor = 2u64.saturating_pow((attempt - 1) as u32);
+		return Some(base.saturating_mul(factor).min(MaxAutoRetryDelay));
+	}
+}
@@ -0,0 +1,179 @@
+#![allow(non_snake_case, non_upper_case_globals)]
+#![cfg_attr(debug_assertions, allow(dead_code))]
+
+use chrono::{Datelike, Duration, NaiveDateTime, NaiveTime};
+use serde::{Deserialize, Serialize};
+use super::size::ByteSize;
+
+const Format_Opening: &str = "%H:%M";
+const Format_OpeningDay: &str = "%a %H:%M";
+const Format_TimeOfDay: &str = "%H:%M";
+/// Days of windows to look through, from yesterday for a window still open
+/// past midnight, to a week ahead for a window which only opens on one day.
+const LookaheadDays: i64 = 8;
+
+/// The days of the week in the order chrono counts them from Monday.
+pub const Weekdays: [&str; 7] = ["Monday", "Tuesday", "Wednesday", "Thursday", "Friday", "Saturday", "Sunday"];
+
+/// Parse a time of day written as HH:MM, e.g. 01:00 or 22:30.
+pub fn parseTimeOfDay(text: String) -> Result<NaiveTime, String>
+{
+	return NaiveTime::parse_from_str(text.trim(), Format_TimeOfDay)
+		.map_err(|_| format!("Invalid time '{}', expected HH:MM such as 01:00", text));
+}
+
+/// Describe when a held download will start, with the day if it is not today.
+pub fn formatOpening(opening: NaiveDateTime, now: NaiveDateTime) -> String
+{
+	let format = match opening.date() == now.date()
+	{
+		true => Format_Opening,
+		false => Format_OpeningDay,
+	};
+	return opening.format(format).to_string();
+}
+
+// --------------------------------------------------
+
+/// A time of day downloads are allowed to run on one day of the week. A
+/// window which ends before it starts runs past midnight into the next day.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default)]
+pub struct ScheduleWindow
+{
+	/// Index into `Weekdays`, 0 for Monday.
+	pub day: usize,
+	pub start: String,
+	pub end: String,
+}
+
+impl Default for ScheduleWindow
+{
+	fn default() -> Self
+	{
+		return Self
+		{
+			day: 0,
+			start: "01:00".to_string(),
+			end: "07:00".to_string(),
+		};
+	}
+}
+
+impl ScheduleWindow
+{
+	pub fn validate(&self) -> Result<(NaiveTime, NaiveTime), String>
+	{
+		if self.day >= Weekdays.len()
+		{
+			return Err(format!("Invalid day {}", self.day));
+		}
+		
+		let start = parseTimeOfDay(self.start.to_owned())?;
+		let end = parseTimeOfDay(self.end.to_owned())?;
+		if start == end
+		{
+			return Err(format!("The window from {} to {} is empty", self.start, self.end));
+		}
+		
+		return Ok((start, end));
+	}
+	
+	/// The times this window opens and closes around the given time.
+	fn occurrences(&self, now: NaiveDateTime) -> Vec<(NaiveDateTime, NaiveDateTime)>
+	{
+		let (start, end) = match self.validate()
+		{
+			Ok(times) => times,
+			Err(_) => return vec![],
+		};
+		
+		return (-1..LookaheadDays)
+			.map(|offset| now.date() + Duration::days(offset))
+			.filter(|date| date.weekday().num_days_from_monday() as usize == self.day)
+			.map(|date| match end > start
+			{
+				true => (date.and_time(start), date.and_time(end)),
+				false => (date.and_time(start), (date + Duration::days(1)).and_time(end)),
+			})
+			.collect();
+	}
+}
+
+// --------------------------------------------------
+
+/// When downloads are allowed to run. New downloads are held outside the
+/// windows, and those still running are limited to the reduced rate.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default)]
+pub struct Schedule
+{
+	pub enabled: bool,
+	/// Budget shared by the running downloads outside the windows, the
+	/// Bandwidth Limit applies all the time when empty.
+	pub outsideRate: String,
+	pub windows: Vec<ScheduleWindow>,
+}
+
+impl Schedule
+{
+	/// Whether downloads may start at the given time. A schedule without any
+	/// valid windows never holds anything back.
+	pub fn isOpen(&self, now: NaiveDateTime) -> bool
+	{
+		let occurrences = self.occurrences(now);
+		return !self.enabled
+			|| occurrences.is_empty()
+			|| occurrences.iter().any(|(opens, closes)| *opens <= now && now < *closes);
+	}
+	
+	/// When the next window opens, or None if one is open now.
+	pub fn nextOpening(&self, now: NaiveDateTime) -> Option<NaiveDateTime>
+	{
+		if self.isOpen(now)
+		{
+			return None;
+		}
+		
+		return self.occurrences(now)
+			.into_iter()
+			.map(|(opens, _)| opens)
+			.filter(|opens| *opens > now)
+			.min();
+	}
+	
+	/// The next time a window opens or closes, when the held and running
+	/// downloads need to be looked at again.
+	pub fn nextBoundary(&self, now: NaiveDateTime) -> Option<NaiveDateTime>
+	{
+		if !self.enabled
+		{
+			return None;
+		}
+		
+		return self.occurrences(now)
+			.into_iter()
+			.flat_map(|(opens, closes)| [opens, closes])
+			.filter(|boundary| *boundary > now)
+			.min();
+	}
+	
+	/// The budget shared by the running downloads, reduced outside the windows.
+	pub fn budget(&self, open: bool, bandwidthLimit: String) -> Option<ByteSize>
+	{
+		let limit = match self.enabled && !open && !self.outsideRate.trim().is_empty()
+		{
+			true => self.outsideRate.to_owned(),
+			false => bandwidthLimit,
+		};
+		return ByteSize::parse(limit).ok();
+	}
+	
+	/// Every opening and closing of the windows around the given time.
+	fn occurrences(&self, now: NaiveDateTime) -> Vec<(NaiveDateTime, NaiveDateTime)>
+	{
+		return self.windows.iter()
+			.flat_map(|window| window.occurrences(now))
+			.collect();
+	}
+}
@@ -0,0 +1,173 @@
+#![allow(non_snake_case, non_upper_case_globals)]
+#![cfg_attr(debug_assertions, allow(dead_code))]
+
+use fancy_regex::Regex;
+use serde::{Deserialize, Serialize};
+
+const Prefix_TimeRange: &str = "*";
+const Separator_TimeRange: &str = "-";
+const Separator_Timestamp: char = ':';
+const Timestamp_Infinite: &str = "inf";
+
+/// A single section of a video to download, as understood by `--download-sections`.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum DownloadSection
+{
+	/// A regular expression matched against the titles of the video's chapters.
+	Chapter(String),
+	/// A start and, optionally, an end timestamp in milliseconds. No end means
+	/// the section runs until the end of the video.
+	TimeRange(u64, Option<u64>),
+}
+
+impl DownloadSection
+{
+	/// Build a time range section from user supplied start and end timestamps.
+	pub fn fromTimestamps(start: String, end: String) -> Result<Self, String>
+	{
+		let startMillis = match start.trim().is_empty()
+		{
+			true => 0,
+			false => parseTimestamp(start.to_owned())?,
+		};
+		
+		let endMillis = match end.trim().is_empty() || end.trim() == Timestamp_Infinite
+		{
+			true => None,
+			false => Some(parseTimestamp(end.to_owned())?),
+		};
+		
+		if let Some(e) = endMillis
+		{
+			if e <= startMillis
+			{
+				return Err(format!("Section end '{}' must come after its start '{}'", end, start));
+			}
+		}
+		
+		return Ok(Self::TimeRange(startMillis, endMillis));
+	}
+	
+	/// Build a chapter section from a user supplied regular expression.
+	pub fn fromChapter(pattern: String) -> Result<Self, String>
+	{
+		if pattern.trim().is_empty()
+		{
+			return Err("Chapter pattern cannot be empty".to_string());
+		}
+		
+		return match Regex::new(pattern.as_str())
+		{
+			Ok(_) => Ok(Self::Chapter(pattern.to_owned())),
+			Err(e) => Err(format!("Invalid chapter pattern '{}': {}", pattern, e)),
+		};
+	}
+	
+	/// Make sure the section fits inside a video of the given duration, in milliseconds.
+	pub fn validate(&self, duration: Option<u64>) -> Result<(), String>
+	{
+		if let (Self::TimeRange(start, end), Some(d)) = (self, duration)
+		{
+			if *start >= d
+			{
+				return Err(format!("Section start {} is beyond the end of the video ({})", formatTimestamp(*start), formatTimestamp(d)));
+			}
+			
+			if let Some(e) = end
+			{
+				if *e > d
+				{
+					return Err(format!("Section end {} is beyond the end of the video ({})", formatTimestamp(*e), formatTimestamp(d)));
+				}
+			}
+		}
+		
+		return Ok(());
+	}
+	
+	pub fn toArgument(&self) -> String
+	{
+		return match self
+		{
+			Self::Chapter(pattern) => pattern.to_owned(),
+			Self::TimeRange(start, end) => {
+				let endString = match end
+				{
+					Some(e) => formatTimestamp(*e),
+					None => Timestamp_Infinite.to_string(),
+				};
+				format!("{}{}{}{}", Prefix_TimeRange, formatTimestamp(*start), Separator_TimeRange, endString)
+			},
+		};
+	}
+}
+
+impl std::fmt::Display for DownloadSection
+{
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+	{
+		let formatted = match self
+		{
+			Self::Chapter(pattern) => format!("Chapters matching '{}'", pattern),
+			Self::TimeRange(start, Some(end)) => format!("{} to {}", formatTimestamp(*start), formatTimestamp(*end)),
+			Self::TimeRange(start, None) => format!("{} to the end", formatTimestamp(*start)),
+		};
+		return f.write_str(formatted.as_str());
+	}
+}
+
+// --------------------------------------------------
+
+/// Format milliseconds as `[HH:]MM:SS[.mmm]`.
+pub fn formatTimestamp(millis: u64) -> String
+{
+	let hours = millis / 3_600_000;
+	let minutes = (millis / 60_000) % 60;
+	let seconds = (millis / 1000) % 60;
+	let fraction = millis % 1000;
+	
+	let mut formatted = match hours > 0
+	{
+		true => format!("{}:{:02}:{:02}", hours, minutes, seconds),
+		false => format!("{:02}:{:02}", minutes, seconds),
+	};
+	
+	if fraction > 0
+	{
+		formatted.push_str(format!(".{:03}", fraction).as_str());
+	}
+	
+	return formatted;
+}
+
+/// Parse `[[HH:]MM:]SS[.fraction]` into milliseconds.
+pub fn parseTimestamp(timestamp: String) -> Result<u64, String>
+{
+	let parts = timestamp.trim()
+		.split(Separator_Timestamp)
+		.collect::<Vec<&str>>();
+	
+	if parts.is_empty() || parts.len() > 3
+	{
+		return Err(format!("Invalid timestamp '{}'", timestamp));
+	}
+	
+	let mut millis = 0;
+	for (i, part) in parts.iter().rev().enumerate()
+	{
+		let value = part.parse::<f64>()
+			.ok()
+			.filter(|v| v.is_finite() && *v >= 0.0)
+			.ok_or(format!("Invalid timestamp '{}'", timestamp))?;
+		
+		//Only the seconds may be fractional and only the higher units may exceed 59
+		if (i > 0 && value.fract() != 0.0) || (i < parts.len() - 1 && value >= 60.0)
+		{
+			return Err(format!("Invalid timestamp '{}'", timestamp));
+		}
+		
+		millis += (value * 1000.0 * 60f64.powi(i as i32)).round() as u64;
+	}
+	
+	return Ok(millis);
+}
@@ -0,0 +1,58 @@
+#![allow(non_snake_case, non_upper_case_globals)]
+#![cfg_attr(debug_assertions, allow(dead_code))]
+
+/// Whether a running download can be suspended in place. yt-dlp is started
+/// in its own session on Unix, so its whole process group, ffmpeg included,
+/// can be signalled at once.
+pub const CanPause: bool = cfg!(unix);
+
+/// Suspend the process group led by the given process with SIGSTOP.
+pub fn pauseProcessGroup(pid: u32) -> Result<(), String>
+{
+	return signalProcessGroup(pid, Signal::Stop);
+}
+
+/// Continue a suspended process group with SIGCONT.
+pub fn resumeProcessGroup(pid: u32) -> Result<(), String>
+{
+	return signalProcessGroup(pid, Signal::Continue);
+}
+
+/// Kill the process group with SIGKILL, which also works while it is suspended.
+pub fn killProcessGroup(pid: u32) -> Result<(), String>
+{
+	return signalProcessGroup(pid, Signal::Kill);
+}
+
+// --------------------------------------------------
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Signal
+{
+	Continue,
+	Kill,
+	Stop,
+}
+
+#[cfg(unix)]
+fn signalProcessGroup(pid: u32, signal: Signal) -> Result<(), String>
+{
+	let sig = match signal
+	{
+		Signal::Continue => libc::SIGCONT,
+		Signal::Kill => libc::SIGKILL,
+		Signal::Stop => libc::SIGSTOP,
+	};
+	
+	return match unsafe { libc::killpg(pid as libc::pid_t, sig) }
+	{
+		0 => Ok(()),
+		_ => Err(format!("Failed to signal process group {}: {}", pid, std::io::Error::last_os_error())),
+	};
+}
+
+#[cfg(not(unix))]
+fn signalProcessGroup(_pid: u32, _signal: Signal) -> Result<(), String>
+{
+	return Err("Pausing a download is only supported on Unix".to_string());
+}
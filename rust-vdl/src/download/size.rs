@@ -0,0 +1,68 @@
+#![allow(non_snake_case, non_upper_case_globals)]
+#![cfg_attr(debug_assertions, allow(dead_code))]
+
+const Units: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+const UnitPrefixes: &str = "KMGT";
+
+/// A number of bytes, parsed from the same kind of shorthand yt-dlp accepts,
+/// e.g. `500M`, `1.5G` or `750KiB`. Multiples are powers of 1024.
+#[derive(Clone, Copy, Debug, Default, Eq, Ord, PartialEq, PartialOrd)]
+pub struct ByteSize(pub u64);
+
+impl ByteSize
+{
+	pub fn parse(text: String) -> Result<Self, String>
+	{
+		let trimmed = text.trim();
+		let split = trimmed.find(|c: char| !c.is_ascii_digit() && c != '.')
+			.unwrap_or(trimmed.len());
+		let (number, unit) = trimmed.split_at(split);
+		
+		let value = number.parse::<f64>()
+			.ok()
+			.filter(|v| v.is_finite() && *v > 0.0)
+			.ok_or(format!("Invalid size '{}', expected e.g. 500M or 1.5G", text))?;
+		
+		let unit = unit.trim().to_uppercase();
+		let exponent = match unit.as_str()
+		{
+			"" | "B" => 0,
+			_ => {
+				let prefix = unit.chars().next().and_then(|c| UnitPrefixes.find(c));
+				match (prefix, unit.get(1..).unwrap_or_default())
+				{
+					(Some(i), "" | "B" | "IB") => i as i32 + 1,
+					_ => return Err(format!("Invalid size unit '{}' in '{}'", unit, text)),
+				}
+			},
+		};
+		
+		return Ok(Self((value * 1024f64.powi(exponent)).round() as u64));
+	}
+	
+	pub fn bytes(&self) -> u64
+	{
+		return self.0;
+	}
+}
+
+impl std::fmt::Display for ByteSize
+{
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+	{
+		let mut value = self.0 as f64;
+		let mut unit = 0;
+		while value >= 1024.0 && unit < Units.len() - 1
+		{
+			value /= 1024.0;
+			unit += 1;
+		}
+		
+		let formatted = match unit
+		{
+			0 => format!("{} {}", self.0, Units[unit]),
+			_ => format!("{:.2} {}", value, Units[unit]),
+		};
+		return f.write_str(formatted.as_str());
+	}
+}
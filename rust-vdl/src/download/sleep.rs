@@ -0,0 +1,37 @@
+#![allow(non_snake_case, non_upper_case_globals)]
+#![cfg_attr(debug_assertions, allow(dead_code))]
+
+/// Parse a number of seconds to sleep, as accepted by yt-dlp's
+/// `--sleep-interval` family of options, e.g. `5` or `0.75`.
+pub fn parseSleepSeconds(seconds: String) -> Result<f64, String>
+{
+	return seconds.trim()
+		.parse::<f64>()
+		.ok()
+		.filter(|v| v.is_finite() && *v >= 0.0)
+		.ok_or(format!("Invalid number of seconds '{}'", seconds));
+}
+
+/// Validate the range yt-dlp picks a random sleep from before each download.
+/// The maximum is only meaningful alongside a minimum no larger than it.
+pub fn validateSleepInterval(minimum: String, maximum: String) -> Result<(), String>
+{
+	let min = match minimum.is_empty()
+	{
+		true => None,
+		false => Some(parseSleepSeconds(minimum)?),
+	};
+	
+	if !maximum.is_empty()
+	{
+		let max = parseSleepSeconds(maximum)?;
+		match min
+		{
+			None => return Err("A maximum sleep interval needs a minimum sleep interval".to_string()),
+			Some(m) if m > max => return Err(format!("The maximum {} is less than the minimum {}", max, m)),
+			_ => {},
+		}
+	}
+	
+	return Ok(());
+}
@@ -0,0 +1,54 @@
+#![allow(non_snake_case, non_upper_case_globals)]
+#![cfg_attr(debug_assertions, allow(dead_code))]
+
+use fs2::available_space;
+use log::warn;
+use std::path::Path;
+use super::size::ByteSize;
+
+/// Free space on the filesystem the path is on. The output directory may not
+/// exist until yt-dlp creates it, so the nearest directory which does is used.
+pub fn freeSpace(path: String) -> Result<ByteSize, String>
+{
+	let target = match path.trim().is_empty()
+	{
+		true => Path::new("."),
+		false => Path::new(path.trim()),
+	};
+	
+	let existing = target.ancestors()
+		.find(|p| p.is_dir())
+		.ok_or(format!("No existing directory in '{}'", path))?;
+	
+	return available_space(existing)
+		.map(ByteSize)
+		.map_err(|e| format!("Failed to read the free space of {}: {}", existing.display(), e));
+}
+
+/// Check that a download of the expected size, plus the safety margin, fits in
+/// the free space at the path. Passes when the free space cannot be read, so a
+/// platform without the information does not block every download.
+pub fn checkDiskSpace(path: String, expected: Option<ByteSize>, margin: Option<ByteSize>) -> Result<(), String>
+{
+	let free = match freeSpace(path)
+	{
+		Ok(free) => free,
+		Err(e) => {
+			warn!("{}", e);
+			return Ok(());
+		},
+	};
+	
+	let needed = ByteSize(expected.map_or(0, |e| e.bytes()).saturating_add(margin.map_or(0, |m| m.bytes())));
+	if free < needed
+	{
+		let detail = match expected
+		{
+			Some(size) => format!("{} for the video and the safety margin", size),
+			None => "for the safety margin".to_string(),
+		};
+		return Err(format!("Not enough free space: {} needed {}, {} free", needed, detail, free));
+	}
+	
+	return Ok(());
+}
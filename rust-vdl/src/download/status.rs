@@ -0,0 +1,57 @@
+#![allow(non_snake_case, non_upper_case_globals)]
+#![cfg_attr(debug_assertions, allow(dead_code))]
+
+/// The lifecycle of a single entry in the download list.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum DownloadStatus
+{
+	Downloading,
+	Failed,
+	Finished,
+	Halted,
+	/// Not started yet, for the given reason.
+	Held(String),
+	/// yt-dlp has been suspended, and keeps its place until it is resumed.
+	Paused,
+	/// Waiting for one of the running downloads to finish.
+	Queued,
+	/// The download failed with a transient error and will be retried
+	/// automatically once the countdown runs out.
+	Retrying { attempt: usize, maxAttempts: usize, seconds: u64 },
+	/// Held until the download schedule opens, at the given time.
+	Scheduled(String),
+	/// yt-dlp chose not to download the video, for the given reason.
+	Skipped(String),
+	/// yt-dlp is sleeping between requests, with this many seconds left.
+	Waiting(u64),
+}
+
+impl Default for DownloadStatus
+{
+	fn default() -> Self
+	{
+		return Self::Downloading;
+	}
+}
+
+impl std::fmt::Display for DownloadStatus
+{
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+	{
+		let formatted = match self
+		{
+			Self::Downloading => "Downloading".to_string(),
+			Self::Failed => "Failed".to_string(),
+			Self::Finished => "Finished".to_string(),
+			Self::Halted => "Halted".to_string(),
+			Self::Held(reason) => format!("Held: {}", reason),
+			Self::Paused => "Paused".to_string(),
+			Self::Queued => "Queued".to_string(),
+			Self::Retrying { attempt, maxAttempts, seconds } => format!("Retrying: attempt {} of {} in {}s", attempt, maxAttempts, seconds),
+			Self::Scheduled(opening) => format!("Scheduled for {}", opening),
+			Self::Skipped(reason) => format!("Skipped: {}", reason),
+			Self::Waiting(seconds) => format!("Waiting: resuming in {}s", seconds),
+		};
+		return f.write_str(formatted.as_str());
+	}
+}
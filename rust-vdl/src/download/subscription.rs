@@ -0,0 +1,161 @@
+#![allow(non_snake_case, non_upper_case_globals)]
+#![cfg_attr(debug_assertions, allow(dead_code))]
+
+use serde::{Deserialize, Serialize};
+use crate::dir::getArchivePath;
+use super::archive::{readArchive, ArchiveEntry};
+use super::filter::MatchFilter;
+use super::metadata::{fetchNewEntries, PlaylistEntry};
+use super::video::VideoDownloaderOptions;
+
+/// The options a subscription's items are downloaded with in place of the
+/// global ones.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default)]
+pub struct SubscriptionProfile
+{
+	/// The archive file shared by the checks and the downloads, the managed
+	/// file in the config directory when empty.
+	pub archivePath: String,
+	/// The global Format when empty.
+	pub format: String,
+	/// Applied when checking too, though only to the fields a flat playlist
+	/// has, such as the title and duration.
+	pub matchFilter: MatchFilter,
+	/// The global Output Path when empty.
+	pub outputPath: String,
+}
+
+impl SubscriptionProfile
+{
+	/// The archive file the subscription's items are recorded in.
+	pub fn archive(&self) -> Option<String>
+	{
+		return match self.archivePath.is_empty()
+		{
+			true => getArchivePath(true).map(|path| path.to_string_lossy().to_string()),
+			false => Some(self.archivePath.to_owned()),
+		};
+	}
+	
+	/// Apply the profile to a copy of the global options for one of the
+	/// subscription's downloads.
+	pub fn apply(&self, options: &mut VideoDownloaderOptions)
+	{
+		//Always record what was downloaded, or the next check would find the same items again
+		options.downloadArchive = true;
+		options.downloadArchivePath = self.archivePath.to_owned();
+		
+		if !self.format.is_empty()
+		{
+			options.format = self.format.to_owned();
+		}
+		
+		if !self.matchFilter.isEmpty()
+		{
+			options.matchFilter = self.matchFilter.to_owned();
+		}
+		
+		if !self.outputPath.is_empty()
+		{
+			options.outputPath = self.outputPath.to_owned();
+		}
+	}
+}
+
+// --------------------------------------------------
+
+/// A channel or playlist which is checked periodically for new items.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default)]
+pub struct Subscription
+{
+	pub url: String,
+	/// The title of the channel or playlist, filled in by the first check.
+	pub title: String,
+	pub enabled: bool,
+	pub profile: SubscriptionProfile,
+	/// Unix timestamp of the last check, 0 if it has never been checked.
+	pub lastChecked: u64,
+	/// Why the last check failed, empty if it succeeded.
+	pub lastError: String,
+	/// Items queued by the last check.
+	pub lastNew: usize,
+	/// Items queued since the subscription was added.
+	pub totalNew: usize,
+}
+
+impl Default for Subscription
+{
+	fn default() -> Self
+	{
+		return Self
+		{
+			url: String::default(),
+			title: String::default(),
+			enabled: true,
+			profile: SubscriptionProfile::default(),
+			lastChecked: 0,
+			lastError: String::default(),
+			lastNew: 0,
+			totalNew: 0,
+		};
+	}
+}
+
+impl Subscription
+{
+	pub fn new(url: String) -> Self
+	{
+		return Self { url, ..Default::default() };
+	}
+	
+	pub fn displayTitle(&self) -> String
+	{
+		return match self.title.is_empty()
+		{
+			true => self.url.to_owned(),
+			false => self.title.to_owned(),
+		};
+	}
+	
+	/// Whether the subscription should be checked, given the time now and
+	/// the interval in seconds. An interval of 0 only checks by hand.
+	pub fn isDue(&self, now: u64, interval: u64) -> bool
+	{
+		return self.enabled && interval > 0 && now.saturating_sub(self.lastChecked) >= interval;
+	}
+}
+
+// --------------------------------------------------
+
+/// List the items of the channel or playlist which are not in the archive yet,
+/// along with its title.
+pub async fn checkSubscription(binary: String, subscription: Subscription) -> Result<(Option<String>, Vec<PlaylistEntry>), String>
+{
+	let archive = subscription.profile.archive();
+	let matchFilter = match subscription.profile.matchFilter.isEmpty()
+	{
+		true => None,
+		false => Some(subscription.profile.matchFilter.toExpression()),
+	};
+	
+	let playlist = fetchNewEntries(binary, subscription.url.to_owned(), archive.to_owned(), matchFilter)
+		.await
+		.ok_or(format!("Failed to list the items of {}", subscription.url))?;
+	
+	let archived = archive.map_or(vec![], readArchive);
+	let entries = playlist.entries.into_iter()
+		.filter(|entry| !entry.url.is_empty() && !isArchived(entry, &archived))
+		.collect();
+	
+	return Ok((playlist.title, entries));
+}
+
+/// yt-dlp leaves archived items out of the listing itself, this catches any
+/// which an extractor lists regardless.
+fn isArchived(entry: &PlaylistEntry, archived: &Vec<ArchiveEntry>) -> bool
+{
+	return archived.iter().any(|a| a.id == entry.id
+		&& entry.extractorKey.as_ref().map_or(true, |key| key.eq_ignore_ascii_case(&a.extractor)));
+}
@@ -1,6 +1,29 @@
 #![allow(non_snake_case, non_upper_case_globals)]
 #![cfg_attr(debug_assertions, allow(dead_code))]
 
+/// Variables commonly used in the main output template.
+pub const OutputTemplateVariables: [OutputTemplateVariable; 8] = [
+	OutputTemplateVariable::Title,
+	OutputTemplateVariable::Identifier,
+	OutputTemplateVariable::UploadDate,
+	OutputTemplateVariable::Uploader,
+	OutputTemplateVariable::Channel,
+	OutputTemplateVariable::PlaylistTitle,
+	OutputTemplateVariable::PlaylistIndex,
+	OutputTemplateVariable::Extension,
+];
+
+/// Variables available to the chapter output template used by `--split-chapters`.
+pub const ChapterTemplateVariables: [OutputTemplateVariable; 7] = [
+	OutputTemplateVariable::Title,
+	OutputTemplateVariable::Identifier,
+	OutputTemplateVariable::SectionTitle,
+	OutputTemplateVariable::SectionNumber,
+	OutputTemplateVariable::SectionStart,
+	OutputTemplateVariable::SectionEnd,
+	OutputTemplateVariable::Extension,
+];
+
 #[derive(Clone, Default)]
 pub struct OutputTemplateBuilder
 {
@@ -61,7 +84,6 @@ impl OutputTemplateBuilder
 			| OutputTemplateVariable::LikeCount
 			| OutputTemplateVariable::DislikeCount
 			| OutputTemplateVariable::RepostCount
-			| OutputTemplateVariable::AverageRating
 			| OutputTemplateVariable::CommentCount
 			| OutputTemplateVariable::AgeLimit
 			| OutputTemplateVariable::StartTime
@@ -73,13 +95,17 @@ impl OutputTemplateBuilder
 			| OutputTemplateVariable::PlaylistCount
 			| OutputTemplateVariable::PlaylistIndex
 			| OutputTemplateVariable::PlaylistAutonumber
-				=> format!("%({})n", s),
+			| OutputTemplateVariable::SectionNumber
+				=> format!("%({})d", s),
+			
+			OutputTemplateVariable::AverageRating
+				=> format!("%({})f", s),
 			
 			_ => format!("%({})s", s),
 		};
 	}
 	
-	fn getVariableString(&self, variable: OutputTemplateVariable) -> String
+	pub fn getVariableString(&self, variable: OutputTemplateVariable) -> String
 	{
 		return match variable
 		{
@@ -139,11 +165,15 @@ impl OutputTemplateBuilder
 			OutputTemplateVariable::WebpageUrlBasename			=> "webpage_url_basename".to_string(),
 			OutputTemplateVariable::WebpageUrlDomain			=> "webpage_url_domain".to_string(),
 			OutputTemplateVariable::OriginalUrl					=> "original_url".to_string(),
+			OutputTemplateVariable::SectionTitle				=> "section_title".to_string(),
+			OutputTemplateVariable::SectionNumber				=> "section_number".to_string(),
+			OutputTemplateVariable::SectionStart				=> "section_start".to_string(),
+			OutputTemplateVariable::SectionEnd					=> "section_end".to_string(),
 		};
 	}
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum OutputTemplateVariable
 {
 	Identifier,
@@ -202,4 +232,8 @@ pub enum OutputTemplateVariable
 	WebpageUrlBasename,
 	WebpageUrlDomain,
 	OriginalUrl,
+	SectionTitle,
+	SectionNumber,
+	SectionStart,
+	SectionEnd,
 }
@@ -1,21 +1,45 @@
 #![allow(non_snake_case, non_upper_case_globals)]
 #![cfg_attr(debug_assertions, allow(dead_code))]
 
-use std::{process::Stdio};
-use futures::StreamExt;
+use std::{process::Stdio, time::Duration};
+use futures::{channel::mpsc::UnboundedReceiver, stream, StreamExt};
 use log::{debug, error, trace, warn};
 use fancy_regex::{Captures, Regex};
 use serde::{Deserialize, Serialize};
-use tokio::process::{Child, Command, ChildStderr, ChildStdout};
-use tokio_util::codec::{FramedRead, LinesCodec};
-use crate::dir::getUserDownloadsDir;
+use tokio::io::AsyncWriteExt;
+use tokio::process::{Child, Command, ChildStderr, ChildStdin, ChildStdout};
+use tokio_util::codec::FramedRead;
+use crate::dir::{getArchivePath, getUserDownloadsDir};
+use super::cookies::{validateCookieFile, CookiesFromBrowser};
+use super::error::{DownloadError, ErrorClassifier};
+use super::filter::{validateDate, MatchComparison, MatchCondition, MatchField, MatchFilter};
+use super::geo::validateCountryCode;
+use super::headers::{validateHeaders, HttpHeader};
+use super::network::{maskArguments, validateProxy, validateSourceAddress};
+use super::paths::OutputPaths;
+use super::playlist::parsePlaylistItems;
+use super::prompt::{isSecretPrompt, OutputLine, PromptLinesCodec};
+use super::retry::{parseRetrySleep, validateRetries, AutoRetry};
+use super::schedule::Schedule;
+use super::sections::{parseTimestamp, DownloadSection};
+use super::size::ByteSize;
+use super::sleep::{parseSleepSeconds, validateSleepInterval};
 
 #[cfg(windows)] extern crate winapi;
 
+const Regex_DownloadArchived: &str = r"\[download\] .* has already been recorded in the archive";
+const Regex_DownloadFileSize: &str = r"\[download\] File is (larger|smaller) than (?:max|min)-filesize";
+const Regex_DownloadFilter: &str = r"\[download\] .* does not pass filter \((.*)\), skipping";
 const Regex_DownloadPlaylistCount: &str = r"\[download\] Downloading item (\d+) of (\d+)";
 const Regex_DownloadTitle: &str = r"\[download\] Destination: (?:.*[\\\/])?(.*)\..{3,4}";
+const Regex_DownloadLocation: &str = r"\[download\] Destination: (.+)";
 const Regex_InfoFormats: &str = r"\[info\].*: Downloading \d+ format\(s\): (.+)";
 const Regex_InfoSubtitles: &str = r"\[info\].*: Downloading subtitles: (.+)";
+const Regex_MergerLocation: &str = r#"\[Merger\] Merging formats into "(.+)""#;
+const Regex_MoveFilesLocation: &str = r#"\[MoveFiles\] Moving file ".+" to "(.+)""#;
+const Regex_Retry: &str = r"Retrying(?: fragment (\d+))? \((\d+)/(\d+|inf)\)";
+const Regex_Sleeping: &str = r"Sleeping (\d+(?:\.\d+)?) seconds";
+const Regex_SplitChapters: &str = r"\[SplitChapters\] Chapter (\d+); Destination: (?:.*[\\\/])?(.*)";
 //const Regex_VideoTitle: &str = r"\[download\] Destination: (?:.*[\\\/])?(.*)(?:\.(.*))(?=\..{3,4})\..{3,4}";
 
 const Separator_PartFormat: &str = "+";
@@ -23,6 +47,40 @@ const Separator_Subtitle: &str = ", ";
 
 // --------------------------------------------------
 
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct DownloadChapter
+{
+	pub fileName: String,
+	pub number: usize,
+}
+
+impl std::fmt::Display for DownloadChapter
+{
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+	{
+		let formatted = format!("Chapter {} written to: '{}'", self.number, self.fileName);
+		return f.write_str(formatted.as_str());
+	}
+}
+
+// --------------------------------------------------
+
+/// The callbacks through which a `VideoDownloader` reports on its download.
+pub struct DownloadHandlers
+{
+	pub chapter: Box<dyn Fn(DownloadChapter) + Send>,
+	pub progress: Box<dyn Fn(DownloadProgress) + Send>,
+	pub prompt: Box<dyn Fn(DownloadPrompt) + Send>,
+	pub reset: Box<dyn Fn(DownloadReset) + Send>,
+	pub skipped: Box<dyn Fn(DownloadSkipped) + Send>,
+	pub started: Box<dyn Fn(DownloadStarted) + Send>,
+	pub stopped: Box<dyn Fn(DownloadStopped) + Send>,
+	pub title: Box<dyn Fn(DownloadTitle) + Send>,
+	pub waiting: Box<dyn Fn(DownloadWaiting) + Send>,
+}
+
+// --------------------------------------------------
+
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
 pub struct DownloadProgress
 {
@@ -84,6 +142,26 @@ unsafe impl Send for DownloadProgress {}
 
 // --------------------------------------------------
 
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct DownloadPrompt
+{
+	pub label: String,
+	pub text: String,
+	/// Hide the answer while it is typed.
+	pub secret: bool,
+}
+
+impl std::fmt::Display for DownloadPrompt
+{
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+	{
+		let formatted = format!("Prompt for '{}': {}", self.label, self.text);
+		return f.write_str(formatted.as_str());
+	}
+}
+
+// --------------------------------------------------
+
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
 pub struct DownloadReset
 {
@@ -103,17 +181,100 @@ impl std::fmt::Display for DownloadReset
 
 // --------------------------------------------------
 
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum SkipReason
+{
+	/// The video is already recorded in the download archive.
+	Archived,
+	/// The video does not pass the match filter shown.
+	Filter(String),
+	/// The file is larger than the maximum file size.
+	TooLarge,
+	/// The file is smaller than the minimum file size.
+	TooSmall,
+}
+
+impl std::fmt::Display for SkipReason
+{
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+	{
+		let formatted = match self
+		{
+			Self::Archived => "already in archive".to_string(),
+			Self::Filter(filter) => format!("does not pass filter ({})", filter),
+			Self::TooLarge => "exceeds size limit".to_string(),
+			Self::TooSmall => "below minimum size".to_string(),
+		};
+		return f.write_str(formatted.as_str());
+	}
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DownloadSkipped
+{
+	pub label: String,
+	pub reason: SkipReason,
+}
+
+impl std::fmt::Display for DownloadSkipped
+{
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+	{
+		let formatted = format!("Download skipped, {}: '{}'", self.reason, self.label);
+		return f.write_str(formatted.as_str());
+	}
+}
+
+// --------------------------------------------------
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct DownloadStarted
+{
+	pub label: String,
+	/// The process id of yt-dlp, which also leads its process group on Unix.
+	pub pid: Option<u32>,
+}
+
+impl std::fmt::Display for DownloadStarted
+{
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+	{
+		let formatted = match self.pid
+		{
+			Some(pid) => format!("Download has started as process {}: '{}'", pid, self.label),
+			None => format!("Download has started: '{}'", self.label),
+		};
+		return f.write_str(formatted.as_str());
+	}
+}
+
+// --------------------------------------------------
+
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
 pub struct DownloadStopped
 {
 	pub label: String,
+	pub success: bool,
+	/// Why the download failed, if yt-dlp or the app reported a reason.
+	pub error: Option<DownloadError>,
+	/// Where the last file ended up, after being moved from the temporary path if one is set.
+	pub location: Option<String>,
 }
 
 impl std::fmt::Display for DownloadStopped
 {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
 	{
-		let formatted = format!("Download has stopped: '{}'", self.label);
+		let outcome = match self.success
+		{
+			true => "successfully",
+			false => "with errors",
+		};
+		let formatted = match &self.error
+		{
+			None => format!("Download has stopped {}: '{}'", outcome, self.label),
+			Some(error) => format!("Download has stopped {} ({}): '{}'", outcome, error, self.label),
+		};
 		return f.write_str(formatted.as_str());
     }
 }
@@ -137,28 +298,143 @@ impl std::fmt::Display for DownloadTitle
 
 // --------------------------------------------------
 
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct DownloadWaiting
+{
+	pub label: String,
+	pub seconds: u64,
+}
+
+impl std::fmt::Display for DownloadWaiting
+{
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+	{
+		let formatted = format!("Waiting {}: {} seconds", self.label, self.seconds);
+		return f.write_str(formatted.as_str());
+	}
+}
+
+// --------------------------------------------------
+
+const Default_ChapterOutputTemplate: &str = "%(title)s - %(section_number)03d %(section_title)s.%(ext)s";
+const Default_DiskSpaceMargin: &str = "500M";
 const Default_Format: &str = "bv*+ba/b";
+const Default_MaxConcurrentDownloads: usize = 3;
+const Default_PauseTimeout: u64 = 600;
+const Default_PromptTimeout: u64 = 120;
+const Default_SubscriptionInterval: u64 = 60;
 const Default_OutputTemplate: &str = "%(upload_date)s - %(title)s.%(ext)s";
 const Option_OutputOnNewLines: &str = "--newline";
+const Prefix_ChapterOutput: &str = "chapter:";
 
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default)]
 pub struct VideoDownloaderOptions
 {
 	pub ageLimit: i64,
+	/// Not passed to yt-dlp, the app retries failed downloads itself.
+	pub autoRetry: AutoRetry,
+	/// Budget shared by all running downloads, each given a part of it with `--limit-rate`.
+	pub bandwidthLimit: String,
+	pub bufferSize: String,
+	pub chapterOutput: String,
+	/// Number of fragments of a DASH or HLS video to download at once, 0 or 1 for one at a time.
+	pub concurrentFragments: usize,
+	/// Resume partially downloaded files, set when retrying a failed download.
+	#[serde(skip)]
+	pub continueDownload: bool,
 	pub convertSubs: String,
 	pub convertThumbnails: String,
+	/// Path to a Netscape cookie file.
+	pub cookies: String,
+	pub cookiesFromBrowser: CookiesFromBrowser,
+	pub date: String,
+	pub dateAfter: String,
+	pub dateBefore: String,
+	/// Free space to leave on top of the expected filesize before starting a download.
+	pub diskSpaceMargin: String,
+	/// Hold and pause the downloads while the output disk has less free space than this.
+	pub diskSpaceMinimum: String,
+	/// Only warn about a download which might not fit rather than refusing to start it.
+	pub diskSpaceWarnOnly: bool,
+	pub downloadArchive: bool,
+	/// The archive file to use, the managed file in the config directory when empty.
+	pub downloadArchivePath: String,
 	pub downloadPlaylist: bool,
+	/// Sections are chosen per download so they are never persisted.
+	#[serde(skip)]
+	pub downloadSections: Vec<DownloadSection>,
 	pub embedMetadata: bool,
 	pub ffmpegLocation: String,
+	pub forceIpv4: bool,
+	pub forceIpv6: bool,
+	pub forceKeyframesAtCuts: bool,
 	pub format: String,
 	pub formatSort: String,
+	pub fragmentRetries: String,
+	/// Fake the X-Forwarded-For header to get around geographic restrictions, on by default in yt-dlp.
+	pub geoBypass: bool,
+	/// ISO 3166-1 alpha-2 code of the country to appear to be in.
+	pub geoBypassCountry: String,
+	/// Proxy used only to verify the IP address for geo-restricted sites.
+	pub geoVerificationProxy: String,
+	/// Custom HTTP headers such as Referer or User-Agent.
+	pub headers: Vec<HttpHeader>,
+	pub httpChunkSize: String,
 	pub limitRate: String,
+	pub matchFilter: MatchFilter,
+	/// Downloads started automatically beyond this many wait in the queue, 0 for no limit.
+	pub maxConcurrentDownloads: usize,
+	pub maxDownloads: usize,
+	pub maxDuration: String,
+	pub maxFilesize: String,
+	pub maxSleepInterval: String,
+	pub minDuration: String,
+	pub minFilesize: String,
+	pub netrc: bool,
+	pub netrcLocation: String,
 	pub output: String,
+	/// The home of the finished downloads, see `paths` for the other kinds of file.
 	pub outputPath: String,
+	/// Seconds a paused download is kept suspended before it is stopped, to be
+	/// continued from its partial files on resume. 0 to keep it suspended.
+	pub pauseTimeout: u64,
+	/// Never saved with the options, see `crate::secrets`.
+	#[serde(skip)]
+	pub password: String,
+	pub paths: OutputPaths,
+	pub playlistItems: String,
+	pub playlistRandom: bool,
+	pub playlistReverse: bool,
 	pub preferFreeFormats: bool,
+	/// Seconds to wait for an answer to a prompt before failing, 0 to wait forever.
+	pub promptTimeout: u64,
+	pub proxy: String,
+	pub retries: String,
+	pub retrySleep: String,
+	/// Not passed to yt-dlp, the app holds downloads until a window opens.
+	pub schedule: Schedule,
+	/// Seconds to sleep before each download, the least when Max Sleep Interval is set.
+	pub sleepInterval: String,
+	/// Seconds to sleep between requests while extracting.
+	pub sleepRequests: String,
+	/// Seconds to sleep before each subtitle download.
+	pub sleepSubtitles: String,
+	/// Seconds to wait before giving up on a connection, 0 for yt-dlp's default.
+	pub socketTimeout: u64,
+	pub sourceAddress: String,
+	pub splitChapters: bool,
 	pub subFormat: String,
 	pub subLangs: String,
+	/// Minutes between checks of the subscriptions, 0 to only check them by hand.
+	pub subscriptionInterval: u64,
+	pub throttledRate: String,
+	/// Single use, so it is only ever asked for at download time.
+	#[serde(skip)]
+	pub twoFactor: String,
 	pub username: String,
+	#[serde(skip)]
+	pub videoPassword: String,
 	pub writeAutoSubs: bool,
 	pub writeSubs: bool,
 	pub writeThumbnail: bool,
@@ -171,20 +447,77 @@ impl Default for VideoDownloaderOptions
 		return Self
 		{
 			ageLimit: 0,
+			autoRetry: AutoRetry::default(),
+			bandwidthLimit: String::default(),
+			bufferSize: String::default(),
+			chapterOutput: Default_ChapterOutputTemplate.to_owned(),
+			concurrentFragments: 0,
+			continueDownload: false,
 			convertSubs: String::default(),
 			convertThumbnails: String::default(),
+			cookies: String::default(),
+			cookiesFromBrowser: CookiesFromBrowser::default(),
+			date: String::default(),
+			dateAfter: String::default(),
+			dateBefore: String::default(),
+			diskSpaceMargin: Default_DiskSpaceMargin.to_string(),
+			diskSpaceMinimum: String::default(),
+			diskSpaceWarnOnly: false,
+			downloadArchive: false,
+			downloadArchivePath: String::default(),
 			downloadPlaylist: false,
+			downloadSections: Vec::<DownloadSection>::default(),
 			embedMetadata: false,
 			ffmpegLocation: String::default(),
+			forceIpv4: false,
+			forceIpv6: false,
+			forceKeyframesAtCuts: false,
 			format: Default_Format.to_string(),
 			formatSort: String::default(),
+			fragmentRetries: String::default(),
+			geoBypass: true,
+			geoBypassCountry: String::default(),
+			geoVerificationProxy: String::default(),
+			headers: Vec::<HttpHeader>::default(),
+			httpChunkSize: String::default(),
 			limitRate: String::default(),
+			matchFilter: MatchFilter::default(),
+			maxConcurrentDownloads: Default_MaxConcurrentDownloads,
+			maxDownloads: 0,
+			maxDuration: String::default(),
+			maxFilesize: String::default(),
+			maxSleepInterval: String::default(),
+			minDuration: String::default(),
+			minFilesize: String::default(),
+			netrc: false,
+			netrcLocation: String::default(),
 			output: Default_OutputTemplate.to_owned(),
 			outputPath: getUserDownloadsDir(),
+			pauseTimeout: Default_PauseTimeout,
+			password: String::default(),
+			paths: OutputPaths::default(),
+			playlistItems: String::default(),
+			playlistRandom: false,
+			playlistReverse: false,
 			preferFreeFormats: false,
+			promptTimeout: Default_PromptTimeout,
+			proxy: String::default(),
+			retries: String::default(),
+			retrySleep: String::default(),
+			schedule: Schedule::default(),
+			sleepInterval: String::default(),
+			sleepRequests: String::default(),
+			sleepSubtitles: String::default(),
+			socketTimeout: 0,
+			sourceAddress: String::default(),
+			splitChapters: false,
 			subFormat: String::default(),
 			subLangs: String::default(),
+			subscriptionInterval: Default_SubscriptionInterval,
+			throttledRate: String::default(),
+			twoFactor: String::default(),
 			username: String::default(),
+			videoPassword: String::default(),
 			writeAutoSubs: false,
 			writeSubs: false,
 			writeThumbnail: false,
@@ -204,6 +537,17 @@ impl VideoDownloaderOptions
 			args.push(self.ageLimit.to_string());
 		}
 		
+		if self.concurrentFragments > 1
+		{
+			args.push("--concurrent-fragments".to_string());
+			args.push(self.concurrentFragments.to_string());
+		}
+		
+		if self.continueDownload
+		{
+			args.push("--continue".to_string());
+		}
+		
 		if !self.convertSubs.is_empty()
 		{
 			args.push("--convert-subs".to_string());
@@ -216,12 +560,57 @@ impl VideoDownloaderOptions
 			args.push(self.convertThumbnails.to_owned());
 		}
 		
+		if !self.cookies.is_empty()
+		{
+			match validateCookieFile(self.cookies.to_owned())
+			{
+				Ok(_) => {
+					args.push("--cookies".to_string());
+					args.push(self.cookies.to_owned());
+				},
+				Err(e) => warn!("Ignoring --cookies: {}", e),
+			}
+		}
+		
+		if let Some(browser) = self.cookiesFromBrowser.toArgument()
+		{
+			args.push("--cookies-from-browser".to_string());
+			args.push(browser);
+		}
+		
+		for (option, value) in [("--date", &self.date), ("--dateafter", &self.dateAfter), ("--datebefore", &self.dateBefore)]
+		{
+			if !value.is_empty()
+			{
+				match validateDate(value.to_owned())
+				{
+					Ok(_) => {
+						args.push(option.to_string());
+						args.push(value.to_owned());
+					},
+					Err(e) => warn!("Ignoring {}: {}", option, e),
+				}
+			}
+		}
+		
+		if let Some(path) = self.archivePath()
+		{
+			args.push("--download-archive".to_string());
+			args.push(path);
+		}
+		
 		match self.downloadPlaylist
 		{
 			true => args.push("--yes-playlist".to_string()),
 			false => args.push("--no-playlist".to_string()),
 		}
 		
+		self.downloadSections.iter()
+			.for_each(|section| {
+				args.push("--download-sections".to_string());
+				args.push(section.toArgument());
+			});
+		
 		match self.embedMetadata
 		{
 			true => args.push("--embed-metadata".to_string()),
@@ -234,6 +623,21 @@ impl VideoDownloaderOptions
 			args.push(self.ffmpegLocation.to_owned());
 		}
 		
+		//yt-dlp only honors one of these, IPv4 takes precedence like the Options panel
+		if self.forceIpv4
+		{
+			args.push("--force-ipv4".to_string());
+		}
+		else if self.forceIpv6
+		{
+			args.push("--force-ipv6".to_string());
+		}
+		
+		if self.forceKeyframesAtCuts && !self.downloadSections.is_empty()
+		{
+			args.push("--force-keyframes-at-cuts".to_string());
+		}
+		
 		if !self.format.is_empty()
 		{
 			args.push("--format".to_string());
@@ -246,22 +650,139 @@ impl VideoDownloaderOptions
 			args.push(self.formatSort.to_owned());
 		}
 		
+		if !self.geoBypass
+		{
+			args.push("--no-geo-bypass".to_string());
+		}
+		else if !self.geoBypassCountry.is_empty()
+		{
+			match validateCountryCode(self.geoBypassCountry.to_owned())
+			{
+				Ok(code) => {
+					args.push("--geo-bypass-country".to_string());
+					args.push(code);
+				},
+				Err(e) => warn!("Ignoring --geo-bypass-country: {}", e),
+			}
+		}
+		
+		if !self.geoVerificationProxy.is_empty()
+		{
+			match validateProxy(self.geoVerificationProxy.to_owned())
+			{
+				Ok(_) => {
+					args.push("--geo-verification-proxy".to_string());
+					args.push(self.geoVerificationProxy.trim().to_owned());
+				},
+				Err(e) => warn!("Ignoring --geo-verification-proxy: {}", e),
+			}
+		}
+		
+		for (header, error) in self.headers.iter().zip(validateHeaders(&self.headers))
+		{
+			match error.is_empty()
+			{
+				true => {
+					args.push("--add-headers".to_string());
+					args.push(header.toArgument());
+				},
+				false => warn!("Ignoring --add-headers: {}", error),
+			}
+		}
+		
 		if !self.limitRate.is_empty()
 		{
 			args.push("--limit-rate".to_string());
 			args.push(self.limitRate.to_owned());
 		}
 		
+		let matchFilter = self.matchFilterWithDurationLimits();
+		if !matchFilter.isEmpty()
+		{
+			args.push("--match-filters".to_string());
+			args.push(matchFilter.toExpression());
+		}
+		
+		if self.maxDownloads > 0
+		{
+			args.push("--max-downloads".to_string());
+			args.push(self.maxDownloads.to_string());
+		}
+		
+		let sizes = [
+			("--buffer-size", &self.bufferSize),
+			("--http-chunk-size", &self.httpChunkSize),
+			("--max-filesize", &self.maxFilesize),
+			("--min-filesize", &self.minFilesize),
+			("--throttled-rate", &self.throttledRate),
+		];
+		
+		for (option, value) in sizes
+		{
+			if !value.is_empty()
+			{
+				match ByteSize::parse(value.to_owned())
+				{
+					Ok(size) => {
+						args.push(option.to_string());
+						args.push(size.bytes().to_string());
+					},
+					Err(e) => warn!("Ignoring {}: {}", option, e),
+				}
+			}
+		}
+		
+		if self.netrc
+		{
+			args.push("--netrc".to_string());
+			
+			if !self.netrcLocation.is_empty()
+			{
+				args.push("--netrc-location".to_string());
+				args.push(self.netrcLocation.to_owned());
+			}
+		}
+		
 		if !self.output.is_empty()
 		{
 			args.push("--output".to_string());
 			args.push(self.output.to_owned());
 		}
 		
-		if !self.outputPath.is_empty()
+		if self.splitChapters && !self.chapterOutput.is_empty()
+		{
+			args.push("--output".to_string());
+			args.push(format!("{}{}", Prefix_ChapterOutput, self.chapterOutput));
+		}
+		
+		args.append(&mut self.paths.toArguments(self.outputPath.to_owned()));
+		
+		if !self.password.is_empty()
+		{
+			args.push("--password".to_string());
+			args.push(self.password.to_owned());
+		}
+		
+		if !self.playlistItems.is_empty()
+		{
+			match parsePlaylistItems(self.playlistItems.to_owned())
+			{
+				Ok(_) => {
+					args.push("--playlist-items".to_string());
+					args.push(self.playlistItems.to_owned());
+				},
+				Err(e) => warn!("Ignoring invalid playlist items '{}': {}", self.playlistItems, e),
+			}
+		}
+		
+		match self.playlistRandom
 		{
-			args.push("--paths".to_string());
-			args.push(self.outputPath.to_owned());
+			true => args.push("--playlist-random".to_string()),
+			false => match self.playlistReverse
+			{
+				true => args.push("--playlist-reverse".to_string()),
+				false => args.push("--no-playlist-reverse".to_string()),
+			},
 		}
 		
 		match self.preferFreeFormats
@@ -270,6 +791,96 @@ impl VideoDownloaderOptions
 			false => args.push("--no-prefer-free-formats".to_string()),
 		}
 		
+		for (option, value) in [("--fragment-retries", &self.fragmentRetries), ("--retries", &self.retries)]
+		{
+			if !value.is_empty()
+			{
+				match validateRetries(value.to_owned())
+				{
+					Ok(_) => {
+						args.push(option.to_string());
+						args.push(value.trim().to_lowercase());
+					},
+					Err(e) => warn!("Ignoring {}: {}", option, e),
+				}
+			}
+		}
+		
+		match parseRetrySleep(self.retrySleep.to_owned())
+		{
+			Ok(list) => list.iter().for_each(|sleep| {
+				args.push("--retry-sleep".to_string());
+				args.push(sleep.to_string());
+			}),
+			Err(e) => warn!("Ignoring --retry-sleep: {}", e),
+		}
+		
+		if !self.proxy.is_empty()
+		{
+			match validateProxy(self.proxy.to_owned())
+			{
+				Ok(_) => {
+					args.push("--proxy".to_string());
+					args.push(self.proxy.trim().to_owned());
+				},
+				Err(e) => warn!("Ignoring --proxy: {}", e),
+			}
+		}
+		
+		match validateSleepInterval(self.sleepInterval.to_owned(), self.maxSleepInterval.to_owned())
+		{
+			Ok(_) => {
+				for (option, value) in [("--sleep-interval", &self.sleepInterval), ("--max-sleep-interval", &self.maxSleepInterval)]
+				{
+					if !value.is_empty()
+					{
+						args.push(option.to_string());
+						args.push(value.trim().to_owned());
+					}
+				}
+			},
+			Err(e) => warn!("Ignoring --sleep-interval: {}", e),
+		}
+		
+		for (option, value) in [("--sleep-requests", &self.sleepRequests), ("--sleep-subtitles", &self.sleepSubtitles)]
+		{
+			if !value.is_empty()
+			{
+				match parseSleepSeconds(value.to_owned())
+				{
+					Ok(_) => {
+						args.push(option.to_string());
+						args.push(value.trim().to_owned());
+					},
+					Err(e) => warn!("Ignoring {}: {}", option, e),
+				}
+			}
+		}
+		
+		if self.socketTimeout > 0
+		{
+			args.push("--socket-timeout".to_string());
+			args.push(self.socketTimeout.to_string());
+		}
+		
+		if !self.sourceAddress.is_empty()
+		{
+			match validateSourceAddress(self.sourceAddress.to_owned())
+			{
+				Ok(_) => {
+					args.push("--source-address".to_string());
+					args.push(self.sourceAddress.trim().to_owned());
+				},
+				Err(e) => warn!("Ignoring --source-address: {}", e),
+			}
+		}
+		
+		match self.splitChapters
+		{
+			true => args.push("--split-chapters".to_string()),
+			false => args.push("--no-split-chapters".to_string()),
+		}
+		
 		if !self.subFormat.is_empty()
 		{
 			args.push("--sub-format".to_string());
@@ -282,12 +893,24 @@ impl VideoDownloaderOptions
 			args.push(self.subLangs.to_owned());
 		}
 		
+		if !self.twoFactor.is_empty()
+		{
+			args.push("--twofactor".to_string());
+			args.push(self.twoFactor.trim().to_owned());
+		}
+		
 		if !self.username.is_empty()
 		{
 			args.push("--username".to_string());
 			args.push(self.username.to_owned());
 		}
 		
+		if !self.videoPassword.is_empty()
+		{
+			args.push("--video-password".to_string());
+			args.push(self.videoPassword.to_owned());
+		}
+		
 		match self.writeAutoSubs
 		{
 			true => args.push("--write-auto-subs".to_string()),
@@ -308,6 +931,45 @@ impl VideoDownloaderOptions
 		
 		return args;
 	}
+	
+	/// Whether yt-dlp would have to prompt for a password it has no way of
+	/// being given, because a username is set without a password or netrc.
+	pub fn needsPassword(&self) -> bool
+	{
+		return !self.username.is_empty() && self.password.is_empty() && !self.netrc;
+	}
+	
+	/// The archive file passed to yt-dlp, if the download archive is enabled.
+	pub fn archivePath(&self) -> Option<String>
+	{
+		return match self.downloadArchive
+		{
+			true if !self.downloadArchivePath.is_empty() => Some(self.downloadArchivePath.to_owned()),
+			true => getArchivePath(true).map(|path| path.to_string_lossy().to_string()),
+			false => None,
+		};
+	}
+	
+	/// The match filter with the minimum and maximum durations, if any,
+	/// added as extra conditions.
+	pub fn matchFilterWithDurationLimits(&self) -> MatchFilter
+	{
+		let mut filter = self.matchFilter.to_owned();
+		
+		for (comparison, value) in [(MatchComparison::GreaterOrEqual, &self.minDuration), (MatchComparison::LessOrEqual, &self.maxDuration)]
+		{
+			if !value.is_empty()
+			{
+				match parseTimestamp(value.to_owned())
+				{
+					Ok(millis) => filter.conditions.push(MatchCondition::Numeric(MatchField::Duration, comparison, (millis / 1000) as i64)),
+					Err(e) => warn!("Ignoring duration limit: {}", e),
+				}
+			}
+		}
+		
+		return filter;
+	}
 }
 
 // --------------------------------------------------
@@ -321,11 +983,25 @@ pub struct VideoDownloader
 	partLabels: Vec<String>,
 	playlistCurrent: usize,
 	playlistMax: usize,
+	lastProgress: DownloadProgress,
+	retryStatus: String,
+	lastError: Option<DownloadError>,
+	location: Option<String>,
 	
+	errorClassifier: ErrorClassifier,
 	regexInfoFormats: Regex,
 	regexInfoSubtitles: Regex,
+	regexDownloadArchived: Regex,
+	regexDownloadFileSize: Regex,
+	regexDownloadFilter: Regex,
+	regexDownloadLocation: Regex,
 	regexDownloadPlaylistCount: Regex,
 	regexDownloadTitle: Regex,
+	regexMergerLocation: Regex,
+	regexMoveFilesLocation: Regex,
+	regexRetry: Regex,
+	regexSleeping: Regex,
+	regexSplitChapters: Regex,
 }
 
 impl VideoDownloader
@@ -335,7 +1011,16 @@ impl VideoDownloader
 		let regexInfoFormats = Regex::new(Regex_InfoFormats).expect("Failed to compile Info Formats regular expression");
 		let regexInfoSubtitles = Regex::new(Regex_InfoSubtitles).expect("Failed to compile Info Subtitles regular expression");
 		let regexDownloadPlaylistCount = Regex::new(Regex_DownloadPlaylistCount).expect("Failed to compile Download Playlist Count regular expression.");
+		let regexDownloadArchived = Regex::new(Regex_DownloadArchived).expect("Failed to compile Download Archived regular expression.");
+		let regexDownloadFileSize = Regex::new(Regex_DownloadFileSize).expect("Failed to compile Download File Size regular expression.");
+		let regexDownloadFilter = Regex::new(Regex_DownloadFilter).expect("Failed to compile Download Filter regular expression.");
+		let regexDownloadLocation = Regex::new(Regex_DownloadLocation).expect("Failed to compile Download Location regular expression.");
 		let regexDownloadTitle = Regex::new(Regex_DownloadTitle).expect("Failed to compile Download Title regular expression.");
+		let regexMergerLocation = Regex::new(Regex_MergerLocation).expect("Failed to compile Merger Location regular expression.");
+		let regexMoveFilesLocation = Regex::new(Regex_MoveFilesLocation).expect("Failed to compile Move Files Location regular expression.");
+		let regexRetry = Regex::new(Regex_Retry).expect("Failed to compile Retry regular expression.");
+		let regexSleeping = Regex::new(Regex_Sleeping).expect("Failed to compile Sleeping regular expression.");
+		let regexSplitChapters = Regex::new(Regex_SplitChapters).expect("Failed to compile Split Chapters regular expression.");
 		
 		return Self
 		{
@@ -346,10 +1031,24 @@ impl VideoDownloader
 			partLabels: Vec::<String>::default(),
 			playlistCurrent: 0,
 			playlistMax: 0,
+			lastProgress: DownloadProgress::default(),
+			retryStatus: String::default(),
+			lastError: None,
+			location: None,
+			errorClassifier: ErrorClassifier::new(),
 			regexInfoFormats,
 			regexInfoSubtitles,
+			regexDownloadArchived,
+			regexDownloadFileSize,
+			regexDownloadFilter,
+			regexDownloadLocation,
 			regexDownloadPlaylistCount,
 			regexDownloadTitle,
+			regexMergerLocation,
+			regexMoveFilesLocation,
+			regexRetry,
+			regexSleeping,
+			regexSplitChapters,
 		};
 	}
 	
@@ -366,12 +1065,7 @@ impl VideoDownloader
 		}
 	}
 	
-	pub async fn download(&mut self, video: String,
-		progressHandler: Box<dyn Fn(DownloadProgress) + Send>,
-		resetHandler: Box<dyn Fn(DownloadReset) + Send>,
-		stoppedHandler: Box<dyn Fn(DownloadStopped) + Send>,
-		titleHandler: Box<dyn Fn(DownloadTitle) + Send>
-	)
+	pub async fn download(&mut self, video: String, handlers: DownloadHandlers, mut answers: UnboundedReceiver<String>)
 	{
 		if !video.is_empty()
 		{
@@ -381,18 +1075,48 @@ impl VideoDownloader
 			generatedArgs.iter().for_each(|s| args.push(s.as_str()));
 			
 			args.push(video.as_str());
+			debug!("Running: {} {}", self.binary, maskArguments(&args));
 			
 			let proc = self.spawnCommand(args.as_mut());
 			match proc
 			{
 				Ok(mut child) => {
-					self.processOutput(
-						child.stdout.take(), child.stderr.take(),
-						progressHandler, resetHandler, stoppedHandler, titleHandler
-					).await;
+					let payload = DownloadStarted { label: video.to_owned(), pid: child.id() };
+					debug!("{}", payload);
+					(handlers.started)(payload);
+					
+					let stdin = child.stdin.take();
+					let failure = self.processOutput(child.stdout.take(), child.stderr.take(), stdin, &mut answers, &handlers).await;
+					
+					//An unanswered prompt leaves the child waiting, so it has to be stopped here
+					if failure.is_some()
+					{
+						if let Err(e) = child.kill().await
+						{
+							error!("{}", e);
+						}
+					}
+					
+					let success = match child.wait().await
+					{
+						Ok(status) => status.success() && failure.is_none(),
+						Err(e) => {
+							error!("{}", e);
+							false
+						},
+					};
+					let error = match success
+					{
+						true => None,
+						false => failure.map(DownloadError::Internal).or(self.lastError.to_owned()),
+					};
+					self.processOutput_downloadStopped(success, error, &handlers.stopped);
 					self.child = Some(child);
 				},
-				Err(e) => error!("Error downloading video: {} -> {}", video, e)
+				Err(e) => {
+					error!("Error downloading video: {} -> {}", video, e);
+					self.processOutput_downloadStopped(false, Some(DownloadError::Internal(e.to_string())), &handlers.stopped);
+				},
 			};
 		}
 	}
@@ -421,96 +1145,189 @@ impl VideoDownloader
 		return (title, partLabel);
 	}
 	
-	async fn processOutput(&mut self, stdout: Option<ChildStdout>, stderr: Option<ChildStderr>,
-		progressHandler: Box<dyn Fn(DownloadProgress) + Send>,
-		resetHandler: Box<dyn Fn(DownloadReset) + Send>,
-		stoppedHandler: Box<dyn Fn(DownloadStopped) + Send>,
-		titleHandler: Box<dyn Fn(DownloadTitle) + Send>
-	)
-	{
-		match stdout
-		{
-			Some(so) => {
-				let mut reader = FramedRead::new(so, LinesCodec::new());
-				while let Some(opt) = reader.next().await
-				{
-					match opt
-					{
-						Ok(line) => {
-							trace!("{}", line);
-							
-							if line.starts_with("[download]")
-							{
-								if let Ok(Some(captures)) = self.regexDownloadPlaylistCount.captures(line.as_str())
-								{
-									self.processOutput_playlistCount(captures, &resetHandler);
-								}
-								else if let Ok(Some(captures)) = self.regexDownloadTitle.captures(line.as_str())
-								{
-									self.processOutput_title(captures, &titleHandler);
-								}
-								else
-								{
-									self.processOutput_downloadProgress(line.to_owned(), &progressHandler);
-								}
-							}
-							else if line.starts_with("[info]")
-							{
-								if let Ok(Some(captures)) = self.regexInfoFormats.captures(line.as_str())
-								{
-									self.processOutput_infoFormats(captures);
-								}
-								else if let Ok(Some(captures)) = self.regexInfoSubtitles.captures(line.as_str())
-								{
-									self.processOutput_infoSubtitles(captures);
-								}
-							}
-						},
-						
-						Err(e) => error!("{}", e),
-					}
-				}
-				
-				self.processOutput_downloadStopped(&stoppedHandler);
+	/// Process the output until the child closes its pipes. Returns the reason
+	/// the download should be failed if a prompt was left unanswered.
+	async fn processOutput(&mut self, stdout: Option<ChildStdout>, stderr: Option<ChildStderr>, mut stdin: Option<ChildStdin>,
+		answers: &mut UnboundedReceiver<String>, handlers: &DownloadHandlers) -> Option<String>
+	{
+		//Read both pipes at once so that warnings on stderr show up as they happen
+		let stdoutLines = match stdout
+		{
+			Some(so) => FramedRead::new(so, PromptLinesCodec::new()).map(|line| (false, line)).boxed(),
+			None => {
+				warn!("No ChildStdout");
+				stream::empty().boxed()
 			},
-			None => warn!("No ChildStdout"),
 		};
 		
-		match stderr
+		let stderrLines = match stderr
 		{
-			Some(se) => {
-				let mut reader = FramedRead::new(se, LinesCodec::new());
-				while let Some(line) = reader.next().await
-				{
-					match line
+			Some(se) => FramedRead::new(se, PromptLinesCodec::new()).map(|line| (true, line)).boxed(),
+			None => {
+				warn!("No ChildStderr");
+				stream::empty().boxed()
+			},
+		};
+		
+		let mut lines = stream::select(stdoutLines, stderrLines);
+		while let Some((isError, opt)) = lines.next().await
+		{
+			match opt
+			{
+				Ok(OutputLine::Prompt(text)) => {
+					if let Err(reason) = self.processOutput_prompt(text, &mut stdin, answers, &handlers.prompt).await
 					{
-						Ok(o) => error!("{}", o),
-						Err(e) => error!("{}", e),
+						error!("{}", reason);
+						return Some(reason);
 					}
+				},
+				Ok(OutputLine::Line(line)) => match isError
+				{
+					true => self.processOutput_error(line, handlers),
+					false => self.processOutput_line(line, handlers),
+				},
+				Err(e) => error!("{}", e),
+			}
+		}
+		
+		return None;
+	}
+	
+	fn processOutput_line(&mut self, line: String, handlers: &DownloadHandlers)
+	{
+		trace!("{}", line);
+		
+		//Sleeping can be reported by the downloader or by an extractor
+		if let Ok(Some(captures)) = self.regexSleeping.captures(line.as_str())
+		{
+			self.processOutput_sleeping(captures, &handlers.waiting);
+		}
+		else if line.starts_with("[download]")
+		{
+			if let Ok(Some(captures)) = self.regexDownloadPlaylistCount.captures(line.as_str())
+			{
+				self.processOutput_playlistCount(captures, &handlers.reset);
+			}
+			else if let Ok(true) = self.regexDownloadArchived.is_match(line.as_str())
+			{
+				self.processOutput_skipped(SkipReason::Archived, &handlers.skipped);
+			}
+			else if let Ok(Some(captures)) = self.regexDownloadFileSize.captures(line.as_str())
+			{
+				let reason = match captures.get(1).map(|m| m.as_str())
+				{
+					Some("smaller") => SkipReason::TooSmall,
+					_ => SkipReason::TooLarge,
+				};
+				self.processOutput_skipped(reason, &handlers.skipped);
+			}
+			else if let Ok(Some(captures)) = self.regexDownloadFilter.captures(line.as_str())
+			{
+				let filter = captures.get(1).map_or(String::default(), |m| m.as_str().to_string());
+				self.processOutput_skipped(SkipReason::Filter(filter), &handlers.skipped);
+			}
+			else if let Ok(Some(captures)) = self.regexDownloadTitle.captures(line.as_str())
+			{
+				if let Ok(Some(location)) = self.regexDownloadLocation.captures(line.as_str())
+				{
+					self.processOutput_location(location);
 				}
-			},
-			None => warn!("No ChildStderr"),
+				self.processOutput_title(captures, &handlers.title);
+			}
+			else
+			{
+				self.processOutput_downloadProgress(line.to_owned(), &handlers.progress);
+			}
+		}
+		else if line.starts_with("[info]")
+		{
+			if let Ok(Some(captures)) = self.regexInfoFormats.captures(line.as_str())
+			{
+				self.processOutput_infoFormats(captures);
+			}
+			else if let Ok(Some(captures)) = self.regexInfoSubtitles.captures(line.as_str())
+			{
+				self.processOutput_infoSubtitles(captures);
+			}
+		}
+		else if line.starts_with("[Merger]")
+		{
+			if let Ok(Some(captures)) = self.regexMergerLocation.captures(line.as_str())
+			{
+				self.processOutput_location(captures);
+			}
+		}
+		else if line.starts_with("[MoveFiles]")
+		{
+			if let Ok(Some(captures)) = self.regexMoveFilesLocation.captures(line.as_str())
+			{
+				self.processOutput_location(captures);
+			}
+		}
+		else if line.starts_with("[SplitChapters]")
+		{
+			if let Ok(Some(captures)) = self.regexSplitChapters.captures(line.as_str())
+			{
+				self.processOutput_splitChapters(captures, &handlers.chapter);
+			}
+		}
+	}
+	
+	fn processOutput_error(&mut self, line: String, handlers: &DownloadHandlers)
+	{
+		if let Ok(Some(captures)) = self.regexRetry.captures(line.as_str())
+		{
+			warn!("{}", line);
+			self.processOutput_retry(captures, &handlers.progress);
+		}
+		else if let Some(classified) = self.errorClassifier.classify(line.as_str())
+		{
+			error!("{}", line);
+			//The first error is the cause, anything after it is fallout
+			if self.lastError.is_none()
+			{
+				self.lastError = Some(classified);
+			}
+		}
+		else
+		{
+			warn!("{}", line);
 		}
 	}
 	
-	fn processOutput_downloadProgress(&self, line: String, handler: &Box<dyn Fn(DownloadProgress) + Send>)
+	fn processOutput_downloadProgress(&mut self, line: String, handler: &Box<dyn Fn(DownloadProgress) + Send>)
 	{
 		let mut payload = DownloadProgress::from(line.to_owned());
 		if !payload.percentComplete.is_empty() || (!payload.size.is_empty() && !payload.time.is_empty() && !payload.transferRate.is_empty())
 		{
 			payload.label = self.currentDownloadLabel.to_owned();
+			//Progress after a retry means the retry succeeded
+			self.retryStatus = String::default();
+			
+			self.lastProgress = payload.to_owned();
 			debug!("{}", payload);
 			(handler)(payload);
 		}
 	}
 	
-	fn processOutput_downloadStopped(&self, handler: &Box<dyn Fn(DownloadStopped) + Send>)
+	fn processOutput_downloadStopped(&self, success: bool, error: Option<DownloadError>, handler: &Box<dyn Fn(DownloadStopped) + Send>)
 	{
-		let payload = DownloadStopped { label: self.currentDownloadLabel.to_owned(), ..Default::default() };
+		let payload = DownloadStopped { label: self.currentDownloadLabel.to_owned(), success, error, location: self.location.to_owned() };
 		debug!("{}", payload);
 		(handler)(payload);
 	}
 	
+	/// Follow the file through downloading, merging and moving, so that where
+	/// it ended up can be reported once the download stops.
+	fn processOutput_location(&mut self, captures: Captures)
+	{
+		if let Some(path) = captures.get(1).map(|m| m.as_str().trim().to_string())
+		{
+			debug!("File location: {}", path);
+			self.location = Some(path);
+		}
+	}
+	
 	fn processOutput_infoFormats(&mut self, captures: Captures)
 	{
 		let m = captures.get(1).map_or(String::default(), |m| m.as_str().to_string());
@@ -557,12 +1374,94 @@ impl VideoDownloader
 		(handler)(payload);
 	}
 	
+	async fn processOutput_prompt(&self, text: String, stdin: &mut Option<ChildStdin>, answers: &mut UnboundedReceiver<String>,
+		handler: &Box<dyn Fn(DownloadPrompt) + Send>) -> Result<(), String>
+	{
+		let payload = DownloadPrompt
+		{
+			label: self.currentDownloadLabel.to_owned(),
+			text: text.to_owned(),
+			secret: isSecretPrompt(text.to_owned()),
+		};
+		debug!("{}", payload);
+		(handler)(payload);
+		
+		let answer = match self.options.promptTimeout
+		{
+			0 => answers.next().await,
+			seconds => match tokio::time::timeout(Duration::from_secs(seconds), answers.next()).await
+			{
+				Ok(answer) => answer,
+				Err(_) => return Err(format!("No answer to '{}' within {} seconds", text, seconds)),
+			},
+		};
+		
+		let answer = answer.ok_or(format!("The prompt '{}' was dismissed", text))?;
+		let pipe = stdin.as_mut().ok_or("No ChildStdin to answer the prompt with".to_string())?;
+		
+		//Never log the answer, it is most likely a password
+		pipe.write_all(format!("{}\n", answer).as_bytes()).await.map_err(|e| e.to_string())?;
+		pipe.flush().await.map_err(|e| e.to_string())?;
+		debug!("Answered prompt for '{}'", self.currentDownloadLabel);
+		return Ok(());
+	}
+	
+	fn processOutput_retry(&mut self, captures: Captures, handler: &Box<dyn Fn(DownloadProgress) + Send>)
+	{
+		let fragment = captures.get(1).map_or(String::default(), |m| m.as_str().to_string());
+		let attempt = captures.get(2).map_or(String::default(), |m| m.as_str().to_string());
+		let maximum = captures.get(3).map_or(String::default(), |m| m.as_str().to_string());
+		
+		self.retryStatus = match fragment.is_empty()
+		{
+			true => format!("retry {}/{}", attempt, maximum),
+			false => format!("retry {}/{} of frag {}", attempt, maximum, fragment),
+		};
+		
+		//Show the attempt straight away instead of waiting for the next progress line
+		let mut payload = self.lastProgress.to_owned();
+		payload.label = self.currentDownloadLabel.to_owned();
+		payload.fragmentStatus = self.retryStatus.to_owned();
+		debug!("{}", payload);
+		(handler)(payload);
+	}
+	
+	fn processOutput_skipped(&self, reason: SkipReason, handler: &Box<dyn Fn(DownloadSkipped) + Send>)
+	{
+		let payload = DownloadSkipped { label: self.currentDownloadLabel.to_owned(), reason };
+		debug!("{}", payload);
+		(handler)(payload);
+	}
+	
+	fn processOutput_sleeping(&self, captures: Captures, handler: &Box<dyn Fn(DownloadWaiting) + Send>)
+	{
+		let seconds = captures.get(1).map_or(0.0, |m| m.as_str().parse::<f64>().unwrap_or_default());
+		let payload = DownloadWaiting { label: self.currentDownloadLabel.to_owned(), seconds: seconds.ceil() as u64 };
+		debug!("{}", payload);
+		(handler)(payload);
+	}
+	
+	fn processOutput_splitChapters(&self, captures: Captures, handler: &Box<dyn Fn(DownloadChapter) + Send>)
+	{
+		let number = captures.get(1).map_or(0, |m| m.as_str().parse::<usize>().unwrap_or_default());
+		let fileName = captures.get(2).map_or(String::default(), |m| m.as_str().to_string());
+		
+		if !fileName.is_empty()
+		{
+			let payload = DownloadChapter { fileName, number };
+			debug!("{}", payload);
+			(handler)(payload);
+		}
+	}
+	
 	fn processOutput_title(&mut self, captures: Captures, handler: &Box<dyn Fn(DownloadTitle) + Send>)
 	{
 		let fullTitle = captures.get(1).map_or(String::default(), |m| m.as_str().to_string());
 		let (title, partLabel) = self.parseTitle(fullTitle);
 		
 		self.updateCurrentDownloadLabel(partLabel.to_owned());
+		self.lastProgress = DownloadProgress::default();
+		self.retryStatus = String::default();
 		
 		let payload = DownloadTitle { title: title.to_owned() };
 		debug!("{}", payload);
@@ -579,6 +1478,7 @@ impl VideoDownloader
 			.creation_flags(winapi::um::winbase::CREATE_NO_WINDOW)
 			.kill_on_drop(true)
 			.stderr(Stdio::piped())
+			.stdin(Stdio::piped())
 			.stdout(Stdio::piped())
 			.args(finalArgs)
 			.spawn();
@@ -590,9 +1490,22 @@ impl VideoDownloader
 		let mut finalArgs = vec![Option_OutputOnNewLines.clone()];
 		finalArgs.append(args);
 		
-		return Command::new(self.binary.to_owned())
+		let mut command = Command::new(self.binary.to_owned());
+		
+		//Start a new session so that yt-dlp has no terminal to prompt on and falls back to stdin
+		unsafe
+		{
+			command.pre_exec(|| match libc::setsid()
+			{
+				-1 => Err(std::io::Error::last_os_error()),
+				_ => Ok(()),
+			});
+		}
+		
+		return command
 			.kill_on_drop(true)
 			.stderr(Stdio::piped())
+			.stdin(Stdio::piped())
 			.stdout(Stdio::piped())
 			.args(finalArgs)
 			.spawn();
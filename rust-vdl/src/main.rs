@@ -8,24 +8,32 @@ mod constants;
 mod download;
 mod dir;
 mod hooks;
+mod secrets;
 mod state;
 
 use dioxus_desktop::Config;
 use dioxus_desktop::tao::menu::{MenuBar, MenuItem, MenuItemAttributes};
 use dioxus_desktop::tao::window::WindowBuilder;
 use crate::components::App;
-use crate::constants::{AppTitle, FileMenuLabel, HelpMenuLabel,
+use crate::constants::{AppTitle, EnvVar_LogsDir, FileMenuLabel, HelpMenuLabel,
 	Log4rsConfigFileName_Debug, Log4rsConfigFileName_Release,
 	MinimumWindowSize, HtmlMain};
+use crate::dir::{getLogsDir, migrateHistory, migrateMisplacedFiles};
 
 fn main()
 {
+	//Without a home directory the logs are written beside the app, as they used to be
+	let logsDir = getLogsDir(true).map_or("logs".to_string(), |dir| dir.to_string_lossy().to_string());
+	std::env::set_var(EnvVar_LogsDir, logsDir);
+	
 	match cfg!(debug_assertions)
 	{
 		true => log4rs::init_file(Log4rsConfigFileName_Debug.to_owned(), Default::default()).unwrap(),
 		false => log4rs::init_file(Log4rsConfigFileName_Release.to_owned(), Default::default()).unwrap(),
 	}
 	
+	migrateMisplacedFiles();
+	migrateHistory();
 	dioxus_desktop::launch_cfg(App, mainWindowConfig());
 }
 
@@ -0,0 +1,154 @@
+#![allow(non_snake_case, non_upper_case_globals)]
+#![cfg_attr(debug_assertions, allow(dead_code))]
+
+use argon2::Argon2;
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::aead::rand_core::RngCore;
+use log::{debug, error};
+use serde::{Deserialize, Serialize};
+use std::fs::{read_to_string, remove_file, write};
+use crate::dir::getSecretsPath;
+
+const KeyLength: usize = 32;
+/// ChaCha20-Poly1305 takes a 96-bit nonce.
+const NonceLength: usize = 12;
+const SaltLength: usize = 16;
+const SecretFileVersion: u32 = 1;
+
+/// Credentials which must never be written to options.json in plain text.
+/// Two-factor codes are single use, so they are always asked for at download
+/// time rather than stored.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default)]
+pub struct Secrets
+{
+	pub password: String,
+	pub videoPassword: String,
+}
+
+impl Secrets
+{
+	pub fn isEmpty(&self) -> bool
+	{
+		return self.password.is_empty() && self.videoPassword.is_empty();
+	}
+}
+
+/// The on-disk form of the secrets: encrypted with ChaCha20-Poly1305 using a
+/// key derived from the master passphrase with Argon2.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+struct SecretFile
+{
+	version: u32,
+	salt: Vec<u8>,
+	nonce: Vec<u8>,
+	ciphertext: Vec<u8>,
+}
+
+/// Whether an encrypted secret file has been saved before.
+pub fn secretsExist() -> bool
+{
+	return getSecretsPath(false).map_or(false, |path| path.exists());
+}
+
+/// Decrypt the secret file with the master passphrase.
+pub fn loadSecrets(passphrase: String) -> Result<Secrets, String>
+{
+	let path = getSecretsPath(false).ok_or("No config directory available".to_string())?;
+	let json = read_to_string(&path).map_err(|e| format!("Failed to read the secret file: {}", e))?;
+	let file = serde_json::from_str::<SecretFile>(json.as_str()).map_err(|e| format!("The secret file is corrupt: {}", e))?;
+	
+	if file.version != SecretFileVersion
+	{
+		return Err(format!("Unsupported secret file version {}", file.version));
+	}
+	
+	//An edited file could hold any number of bytes, which would panic when used as a nonce
+	if file.nonce.len() != NonceLength || file.salt.len() != SaltLength
+	{
+		return Err("The secret file is corrupt".to_string());
+	}
+	
+	let cipher = newCipher(passphrase, &file.salt)?;
+	//A wrong passphrase derives a different key, which fails authentication
+	let plaintext = cipher.decrypt(Nonce::from_slice(&file.nonce), file.ciphertext.as_ref())
+		.map_err(|_| "Wrong passphrase or the secret file has been tampered with".to_string())?;
+	
+	let secrets = serde_json::from_slice::<Secrets>(&plaintext).map_err(|e| format!("The secret file is corrupt: {}", e))?;
+	debug!("Secrets loaded!");
+	return Ok(secrets);
+}
+
+/// Encrypt the secrets with the master passphrase and save them, replacing
+/// any existing secret file.
+pub fn saveSecrets(secrets: &Secrets, passphrase: String) -> Result<(), String>
+{
+	if passphrase.is_empty()
+	{
+		return Err("A master passphrase is required".to_string());
+	}
+	
+	let mut salt = vec![0u8; SaltLength];
+	OsRng.fill_bytes(&mut salt);
+	
+	let cipher = newCipher(passphrase, &salt)?;
+	let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+	let plaintext = serde_json::to_vec(secrets).map_err(|e| e.to_string())?;
+	let ciphertext = cipher.encrypt(&nonce, plaintext.as_ref()).map_err(|e| e.to_string())?;
+	
+	let file = SecretFile
+	{
+		version: SecretFileVersion,
+		salt,
+		nonce: nonce.to_vec(),
+		ciphertext,
+	};
+	
+	let path = getSecretsPath(true).ok_or("No config directory available".to_string())?;
+	let json = serde_json::to_string(&file).map_err(|e| e.to_string())?;
+	return match write(&path, json)
+	{
+		Ok(_) => {
+			debug!("Secrets saved!");
+			Ok(())
+		},
+		Err(e) => {
+			error!("{}", e);
+			Err(format!("Failed to save the secret file: {}", e))
+		},
+	};
+}
+
+/// Delete the secret file, for when the passphrase is lost or the passwords
+/// are no longer wanted.
+pub fn deleteSecrets() -> Result<(), String>
+{
+	let path = getSecretsPath(false).ok_or("No config directory available".to_string())?;
+	if !path.exists()
+	{
+		return Ok(());
+	}
+	
+	return match remove_file(&path)
+	{
+		Ok(_) => {
+			debug!("Secrets deleted!");
+			Ok(())
+		},
+		Err(e) => {
+			error!("{}", e);
+			Err(format!("Failed to delete the secret file: {}", e))
+		},
+	};
+}
+
+fn newCipher(passphrase: String, salt: &[u8]) -> Result<ChaCha20Poly1305, String>
+{
+	let mut key = [0u8; KeyLength];
+	Argon2::default()
+		.hash_password_into(passphrase.as_bytes(), salt, &mut key)
+		.map_err(|e| format!("Failed to derive the key: {}", e))?;
+	
+	return Ok(ChaCha20Poly1305::new(Key::from_slice(&key)));
+}
@@ -5,29 +5,103 @@ use dioxus::prelude::Scope;
 use fermi::prelude::*;
 use log::{debug, error};
 use serde::{Deserialize, Serialize};
-use std::collections::BTreeMap;
-use std::fs::File;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::{File, OpenOptions};
 use std::io::{Read, Write};
-use crate::download::VideoDownloaderOptions;
-use crate::dir::getOptionsPath;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+use crate::download::{ByteSize, Subscription, SubscriptionProfile, VideoDownloaderOptions};
+use crate::dir::{getHistoryPath, getOptionsPath, getSubscriptionsPath};
+use crate::secrets::Secrets;
 
 const DefaultBinary: &'static str = "yt-dlp";
 
+/// The entries with a running yt-dlp process, and the rate each was given by hand if any.
+/// Keyed by `nextDownloadId`, since the list keys repeat inside playlist groups.
+pub static ActiveDownloads: AtomRef<BTreeMap<usize, Option<ByteSize>>> = |_| BTreeMap::<usize, Option<ByteSize>>::default();
 pub static Binary: Atom<String> = |_| DefaultBinary.to_string();
 pub static DownloaderOptions: AtomRef<VideoDownloaderOptions> = |_| VideoDownloaderOptions::default();
-pub static UrlList: AtomRef<BTreeMap<usize, String>> = |_| BTreeMap::<usize, String>::default();
+/// The entries holding one of the Max Concurrent Downloads, keyed by `nextDownloadId`.
+pub static DownloadSlots: AtomRef<BTreeSet<usize>> = |_| BTreeSet::<usize>::default();
+pub static ExpandPlaylists: Atom<bool> = |_| false;
+/// The free space on the output disk while it is below the Disk Space Minimum.
+pub static LowDiskSpace: Atom<Option<ByteSize>> = |_| None;
+/// Whether the download schedule allows downloads to start right now.
+pub static ScheduleOpen: Atom<bool> = |_| true;
+pub static ShowOptions: Atom<bool> = |_| false;
+pub static ShowSubscriptions: Atom<bool> = |_| false;
+pub static Subscriptions: AtomRef<Vec<Subscription>> = |_| Vec::<Subscription>::default();
+/// Passwords entered or unlocked this session, kept out of the options.
+pub static UnlockedSecrets: AtomRef<Secrets> = |_| Secrets::default();
+pub static UrlList: AtomRef<BTreeMap<usize, DownloadEntry>> = |_| BTreeMap::<usize, DownloadEntry>::default();
+
+static DownloadIdCounter: AtomicUsize = AtomicUsize::new(0);
+
+/// An id for a download entry which is unique for as long as the app runs.
+pub fn nextDownloadId() -> usize
+{
+	return DownloadIdCounter.fetch_add(1, Ordering::Relaxed);
+}
+
+/// A URL added to the download list.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct DownloadEntry
+{
+	pub url: String,
+	/// Expand the URL into one entry per video when it is a playlist or channel.
+	pub expandPlaylist: bool,
+	/// The options of the subscription which found the URL, if one did.
+	pub profile: Option<SubscriptionProfile>,
+}
+
+/// Add a URL to the end of the download list, unless it is already there.
+pub fn enqueueDownload(urls: &mut BTreeMap<usize, DownloadEntry>, entry: DownloadEntry) -> bool
+{
+	if urls.values().any(|v| v.url == entry.url)
+	{
+		return false;
+	}
+	
+	let key = urls.keys().last().map_or(0, |k| k + 1);
+	urls.insert(key, entry);
+	return true;
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct HistoryRecord
+{
+	pub url: String,
+	pub title: String,
+	pub status: String,
+	pub timestamp: u64,
+}
+
+impl HistoryRecord
+{
+	pub fn new(url: String, title: String, status: String) -> Self
+	{
+		let timestamp = SystemTime::now()
+			.duration_since(UNIX_EPOCH)
+			.map_or(0, |d| d.as_secs());
+		
+		return Self { url, title, status, timestamp };
+	}
+}
 
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 struct OptionsData
 {
 	pub binary: String,
 	pub downloaderOptions: VideoDownloaderOptions,
+	#[serde(default)]
+	pub expandPlaylists: bool,
 }
 
 pub fn loadOptions(cx: Scope)
 {
 	let setBinary = use_set(cx, Binary);
 	let downloaderOptions = use_atom_ref(cx, DownloaderOptions);
+	let setExpandPlaylists = use_set(cx, ExpandPlaylists);
 	
 	if let Some(path) = getOptionsPath(false)
 	{
@@ -40,6 +114,7 @@ pub fn loadOptions(cx: Scope)
 				{
 					setBinary(data.binary);
 					*downloaderOptions.write() = data.downloaderOptions;
+					setExpandPlaylists(data.expandPlaylists);
 					debug!("Options loaded!")
 				}
 			}
@@ -51,11 +126,19 @@ pub fn saveOptions(cx: Scope)
 {
 	let binary = use_read(cx, Binary);
 	let downloaderOptions = use_atom_ref(cx, DownloaderOptions);
+	let expandPlaylists = use_read(cx, ExpandPlaylists);
 	
+	writeOptions(binary.into(), downloaderOptions.read().to_owned(), *expandPlaylists);
+}
+
+/// Save the options from outside of a component's render, e.g. in a task.
+pub fn writeOptions(binary: String, downloaderOptions: VideoDownloaderOptions, expandPlaylists: bool)
+{
 	let data = OptionsData
 	{
-		binary: binary.into(),
-		downloaderOptions: downloaderOptions.read().to_owned(),
+		binary,
+		downloaderOptions,
+		expandPlaylists,
 	};
 	
 	if let Some(path) = getOptionsPath(true)
@@ -73,3 +156,66 @@ pub fn saveOptions(cx: Scope)
 		}
 	}
 }
+
+pub fn loadSubscriptions() -> Vec<Subscription>
+{
+	let mut subscriptions = vec![];
+	if let Some(path) = getSubscriptionsPath(false)
+	{
+		if let Ok(mut file) = File::open(&path)
+		{
+			let mut json = String::new();
+			if let Ok(_) = file.read_to_string(&mut json)
+			{
+				match serde_json::from_str::<Vec<Subscription>>(json.as_str())
+				{
+					Ok(list) => {
+						subscriptions = list;
+						debug!("Subscriptions loaded!");
+					},
+					Err(e) => error!("{}", e),
+				}
+			}
+		}
+	}
+	
+	return subscriptions;
+}
+
+pub fn saveSubscriptions(subscriptions: &Vec<Subscription>)
+{
+	if let Some(path) = getSubscriptionsPath(true)
+	{
+		if let Ok(json) = serde_json::to_string(subscriptions)
+		{
+			if let Ok(mut file) = File::create(&path)
+			{
+				match file.write_all(json.as_bytes())
+				{
+					Ok(_) => debug!("Subscriptions saved!"),
+					Err(e) => error!("{}", e),
+				}
+			}
+		}
+	}
+}
+
+/// Append a record of a finished or failed download to the history file.
+pub fn recordHistory(record: HistoryRecord)
+{
+	if let Some(path) = getHistoryPath(true)
+	{
+		if let Ok(json) = serde_json::to_string(&record)
+		{
+			match OpenOptions::new().create(true).append(true).open(&path)
+			{
+				Ok(mut file) => match writeln!(file, "{}", json)
+				{
+					Ok(_) => debug!("History recorded for: {}", record.url),
+					Err(e) => error!("{}", e),
+				},
+				Err(e) => error!("{}", e),
+			}
+		}
+	}
+}